@@ -0,0 +1,187 @@
+use std::f32::consts::PI;
+
+use nalgebra_glm::{Vec3, Vec4};
+
+use crate::color::Color;
+use crate::fragment::Fragment;
+use crate::shaders::ShaderOutput;
+use crate::Uniforms;
+
+// Muestras a lo largo del rayo de vista y hacia cada luz
+const VIEW_SAMPLES: usize = 12;
+const LIGHT_SAMPLES: usize = 6;
+
+// Atmósfera de un planeta, dibujada como un cascarón aditivo alrededor de él.
+// Las longitudes (espesor y alturas de escala) son fracciones del radio del
+// planeta y los coeficientes de dispersión están por radio de planeta, así la
+// misma atmósfera sirve para cualquier tamaño.
+#[derive(Debug, Clone, Copy)]
+pub struct Atmosphere {
+    pub thickness: f32,             // Altura del borde superior
+    pub rayleigh: Vec3,             // Coeficiente de dispersión Rayleigh por canal (r, g, b)
+    pub rayleigh_scale_height: f32, // Altura a la que la densidad cae a 1/e
+    pub mie: f32,                   // Coeficiente de dispersión Mie (aerosoles, igual en todos los canales)
+    pub mie_scale_height: f32,
+    pub mie_anisotropy: f32,        // g de Henyey-Greenstein: > 0 dispersa hacia adelante
+    pub intensity: f32,             // Brillo de la luz dispersada
+    pub color: Color,               // Tinte final
+}
+
+impl Default for Atmosphere {
+    // Parecida a la de la Tierra, con el espesor exagerado para que se vea
+    fn default() -> Self {
+        Atmosphere {
+            thickness: 0.15,
+            rayleigh: Vec3::new(1.8, 4.3, 10.6),
+            rayleigh_scale_height: 0.025,
+            mie: 2.5,
+            mie_scale_height: 0.01,
+            mie_anisotropy: 0.76,
+            intensity: 20.0,
+            color: Color::rgb(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+// Dispersión simple analítica: recorre el rayo de vista dentro del cascarón y
+// en cada muestra suma la luz de cada estrella atenuada por el camino
+// (estrella -> muestra -> cámara). El camino largo hacia la luz cerca del
+// terminador deja pasar sobre todo el rojo; el camino largo por el borde da el
+// halo azul del limbo.
+//
+// El cascarón es la esfera unitaria del modelo: su centro y radio salen de la
+// matriz de modelo, y el radio del planeta es el del cascarón / (1 + thickness).
+pub fn atmosphere_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
+    let transparent = Color::black().with_alpha(0.0);
+    let Some(atmosphere) = &uniforms.atmosphere else {
+        return transparent.into();
+    };
+
+    let model = &uniforms.model_matrix;
+    let center = Vec3::new(model[(0, 3)], model[(1, 3)], model[(2, 3)]);
+    let outer_radius = 0.5 * (model * Vec4::new(1.0, 0.0, 0.0, 0.0)).xyz().magnitude();
+    let planet_radius = outer_radius / (1.0 + atmosphere.thickness);
+
+    let eye = uniforms.camera_position;
+    let to_fragment = fragment.world_position - eye;
+    let direction = to_fragment.normalize();
+    let Some((entry, exit)) = ray_sphere(eye, direction, center, outer_radius) else {
+        return transparent.into();
+    };
+
+    // Sólo la cara del cascarón que mira a la cámara aporta; la de atrás
+    // repetiría el mismo rayo
+    if entry > 0.0 && to_fragment.magnitude() > entry + 0.05 * outer_radius {
+        return transparent.into();
+    }
+
+    let start = entry.max(0.0);
+    let end = match ray_sphere(eye, direction, center, planet_radius) {
+        Some((ground, _)) if ground > 0.0 => ground.min(exit),
+        _ => exit,
+    };
+    if end <= start {
+        return transparent.into();
+    }
+
+    let rayleigh = atmosphere.rayleigh;
+    let mie_extinction = atmosphere.mie * 1.1;
+    let density = |point: Vec3| {
+        let height = ((point - center).magnitude() - planet_radius).max(0.0) / planet_radius;
+        (
+            (-height / atmosphere.rayleigh_scale_height).exp(),
+            (-height / atmosphere.mie_scale_height).exp(),
+        )
+    };
+
+    let step = (end - start) / VIEW_SAMPLES as f32;
+    let step_relative = step / planet_radius;
+    let mut scattered = Vec3::zeros();
+    let mut view_depth = (0.0, 0.0);
+
+    for i in 0..VIEW_SAMPLES {
+        let point = eye + direction * (start + step * (i as f32 + 0.5));
+        let (rayleigh_density, mie_density) = density(point);
+        view_depth.0 += rayleigh_density * step_relative;
+        view_depth.1 += mie_density * step_relative;
+
+        for (index, light) in uniforms.lights.iter().enumerate() {
+            let to_light = (light.position - point).normalize();
+
+            // En la sombra del planeta no llega luz
+            if matches!(ray_sphere(point, to_light, center, planet_radius), Some((_, far)) if far > 0.0) {
+                continue;
+            }
+            let visibility = uniforms
+                .shadow_maps
+                .get(index)
+                .map_or(1.0, |shadow_map| shadow_map.visibility(point, point - center));
+            if visibility <= 0.0 {
+                continue;
+            }
+
+            let light_depth = optical_depth(point, to_light, center, outer_radius, planet_radius, &density);
+            let optical = rayleigh * (view_depth.0 + light_depth.0)
+                + Vec3::repeat(mie_extinction * (view_depth.1 + light_depth.1));
+            let transmittance = optical.map(|depth| (-depth).exp());
+
+            let cos_angle = direction.dot(&to_light);
+            let phase = rayleigh * (rayleigh_phase(cos_angle) * rayleigh_density)
+                + Vec3::repeat(atmosphere.mie * mie_phase(cos_angle, atmosphere.mie_anisotropy) * mie_density);
+            scattered += phase.component_mul(&transmittance) * (step_relative * light.intensity * visibility);
+        }
+    }
+
+    let light = scattered * atmosphere.intensity;
+    let tint = atmosphere.color;
+    Color::rgb(light.x * tint.r, light.y * tint.g, light.z * tint.b).into()
+}
+
+// Densidad acumulada (Rayleigh, Mie) desde `origin` hasta salir del cascarón
+fn optical_depth(
+    origin: Vec3,
+    direction: Vec3,
+    center: Vec3,
+    outer_radius: f32,
+    planet_radius: f32,
+    density: &impl Fn(Vec3) -> (f32, f32),
+) -> (f32, f32) {
+    let Some((_, exit)) = ray_sphere(origin, direction, center, outer_radius) else {
+        return (0.0, 0.0);
+    };
+
+    let step = exit.max(0.0) / LIGHT_SAMPLES as f32;
+    let mut depth = (0.0, 0.0);
+    for i in 0..LIGHT_SAMPLES {
+        let (rayleigh, mie) = density(origin + direction * (step * (i as f32 + 0.5)));
+        depth.0 += rayleigh;
+        depth.1 += mie;
+    }
+
+    let step_relative = step / planet_radius;
+    (depth.0 * step_relative, depth.1 * step_relative)
+}
+
+// Distancias de entrada y salida del rayo (origen + t * dirección unitaria) a la esfera
+fn ray_sphere(origin: Vec3, direction: Vec3, center: Vec3, radius: f32) -> Option<(f32, f32)> {
+    let offset = origin - center;
+    let b = offset.dot(&direction);
+    let c = offset.dot(&offset) - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    Some((-b - root, -b + root))
+}
+
+fn rayleigh_phase(cos_angle: f32) -> f32 {
+    3.0 / (16.0 * PI) * (1.0 + cos_angle * cos_angle)
+}
+
+// Henyey-Greenstein
+fn mie_phase(cos_angle: f32, g: f32) -> f32 {
+    let g2 = g * g;
+    (1.0 - g2) / (4.0 * PI * (1.0 + g2 - 2.0 * g * cos_angle).powf(1.5))
+}
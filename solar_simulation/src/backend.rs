@@ -0,0 +1,102 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
+
+use crate::framebuffer::Framebuffer;
+
+// Destino de los cuadros renderizados. El bucle principal sólo habla con este
+// trait, así puede correr con ventana o sin pantalla (CI, servidores).
+pub trait Backend {
+    fn is_open(&self) -> bool;
+    fn is_key_down(&self, key: Key) -> bool;
+    fn is_key_pressed(&self, key: Key) -> bool;
+    fn set_title(&mut self, title: &str);
+    fn present(&mut self, framebuffer: &Framebuffer) -> io::Result<()>;
+}
+
+// Presenta los cuadros en una ventana de minifb
+pub struct WindowBackend {
+    window: Window,
+    frame_delay: Duration,
+}
+
+impl WindowBackend {
+    pub fn new(title: &str, width: usize, height: usize, frame_delay: Duration) -> Self {
+        let mut window = Window::new(title, width, height, WindowOptions::default())
+            .unwrap_or_else(|err| panic!("No se pudo crear la ventana: {} (usa --headless sin pantalla)", err));
+
+        window.set_position(500, 500);
+        window.update();
+
+        WindowBackend { window, frame_delay }
+    }
+}
+
+impl Backend for WindowBackend {
+    fn is_open(&self) -> bool {
+        self.window.is_open()
+    }
+
+    fn is_key_down(&self, key: Key) -> bool {
+        self.window.is_key_down(key)
+    }
+
+    fn is_key_pressed(&self, key: Key) -> bool {
+        self.window.is_key_pressed(key, KeyRepeat::No)
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.window.set_title(title);
+    }
+
+    fn present(&mut self, framebuffer: &Framebuffer) -> io::Result<()> {
+        self.window
+            .update_with_buffer(&framebuffer.buffer, framebuffer.width, framebuffer.height)
+            .map_err(|err| io::Error::other(err.to_string()))?;
+
+        std::thread::sleep(self.frame_delay);
+        Ok(())
+    }
+}
+
+// Renderiza una cantidad fija de cuadros y los guarda (PNG o PPM) en un directorio
+pub struct HeadlessBackend {
+    out_dir: PathBuf,
+    extension: String,
+    frames: usize,
+    frame: usize,
+}
+
+impl HeadlessBackend {
+    pub fn new(out_dir: PathBuf, extension: &str, frames: usize) -> io::Result<Self> {
+        fs::create_dir_all(&out_dir)?;
+        Ok(HeadlessBackend { out_dir, extension: extension.to_string(), frames, frame: 0 })
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn is_open(&self) -> bool {
+        self.frame < self.frames
+    }
+
+    // Sin pantalla no hay teclado
+    fn is_key_down(&self, _key: Key) -> bool {
+        false
+    }
+
+    fn is_key_pressed(&self, _key: Key) -> bool {
+        false
+    }
+
+    fn set_title(&mut self, _title: &str) {}
+
+    fn present(&mut self, framebuffer: &Framebuffer) -> io::Result<()> {
+        let path = self.out_dir.join(format!("frame_{:05}.{}", self.frame, self.extension));
+        framebuffer.save(path)?;
+        self.frame += 1;
+        Ok(())
+    }
+}
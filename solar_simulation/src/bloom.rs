@@ -0,0 +1,87 @@
+use rayon::prelude::*;
+
+use crate::color::Color;
+use crate::framebuffer::Framebuffer;
+
+// Parámetros del resplandor alrededor de las superficies emisivas
+#[derive(Debug, Clone, Copy)]
+pub struct Bloom {
+    pub threshold: f32, // Luminancia lineal a partir de la cual algo brilla
+    pub sigma: f32,     // Desviación estándar del desenfoque, en píxeles
+    pub strength: f32,  // Cuánto del resplandor se suma a la imagen
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Bloom { threshold: 0.3, sigma: 6.0, strength: 1.0 }
+    }
+}
+
+// Toma lo que supera el umbral en el buffer emisivo, lo desenfoca con un
+// gaussiano separable (horizontal y luego vertical) y lo suma a la imagen HDR.
+pub fn apply_bloom(framebuffer: &mut Framebuffer, bloom: &Bloom) {
+    let width = framebuffer.width;
+    let height = framebuffer.height;
+    if width == 0 || height == 0 || bloom.strength <= 0.0 || bloom.sigma <= 0.0 {
+        return;
+    }
+
+    let bright: Vec<Color> = framebuffer
+        .emissive_buffer
+        .par_iter()
+        .map(|&color| bright_pass(color, bloom.threshold))
+        .collect();
+
+    let kernel = gaussian_kernel(bloom.sigma);
+    let radius = kernel.len() as isize / 2;
+
+    // Pasada horizontal
+    let mut horizontal = vec![Color::black(); width * height];
+    horizontal.par_chunks_mut(width).enumerate().for_each(|(y, row)| {
+        let source = &bright[y * width..(y + 1) * width];
+        for (x, out) in row.iter_mut().enumerate() {
+            *out = convolve(&kernel, |k| {
+                let sx = (x as isize + k - radius).clamp(0, width as isize - 1) as usize;
+                source[sx]
+            });
+        }
+    });
+
+    // Pasada vertical y composición
+    framebuffer.hdr.par_chunks_mut(width).enumerate().for_each(|(y, row)| {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let glow = convolve(&kernel, |k| {
+                let sy = (y as isize + k - radius).clamp(0, height as isize - 1) as usize;
+                horizontal[sy * width + x]
+            });
+            *pixel = *pixel + glow * bloom.strength;
+        }
+    });
+}
+
+// Conserva sólo la parte de la luminancia que pasa el umbral
+fn bright_pass(color: Color, threshold: f32) -> Color {
+    let luminance = color.luminance();
+    if luminance <= threshold {
+        return Color::black();
+    }
+
+    color * ((luminance - threshold) / luminance)
+}
+
+// Pesos normalizados de un gaussiano de radio 3 sigma
+fn gaussian_kernel(sigma: f32) -> Vec<f32> {
+    let radius = (sigma * 3.0).ceil() as isize;
+    let weights: Vec<f32> = (-radius..=radius)
+        .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = weights.iter().sum();
+    weights.iter().map(|w| w / total).collect()
+}
+
+fn convolve(kernel: &[f32], sample: impl Fn(isize) -> Color) -> Color {
+    kernel
+        .iter()
+        .enumerate()
+        .fold(Color::black(), |sum, (k, weight)| sum + sample(k as isize) * *weight)
+}
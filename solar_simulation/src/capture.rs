@@ -0,0 +1,80 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::framebuffer::Framebuffer;
+
+// Capturas de pantalla y grabación de secuencias de imágenes numeradas.
+// Como la simulación avanza con un paso fijo por cuadro, cada imagen grabada
+// corresponde exactamente a ese paso sin importar cuánto tarde en guardarse.
+pub struct Capture {
+    screenshot_dir: PathBuf,
+    recording_dir: PathBuf,
+    screenshot_count: usize,
+    take: Option<Take>,
+}
+
+struct Take {
+    dir: PathBuf,
+    frame: usize,
+}
+
+impl Capture {
+    pub fn new(screenshot_dir: PathBuf, recording_dir: PathBuf) -> Self {
+        Capture {
+            screenshot_dir,
+            recording_dir,
+            screenshot_count: 0,
+            take: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.take.is_some()
+    }
+
+    pub fn screenshot(&mut self, framebuffer: &Framebuffer) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.screenshot_dir)?;
+
+        // Busca el primer nombre libre para no pisar capturas de otras sesiones
+        let path = loop {
+            self.screenshot_count += 1;
+            let path = self.screenshot_dir.join(format!("screenshot_{:04}.png", self.screenshot_count));
+            if !path.exists() {
+                break path;
+            }
+        };
+
+        framebuffer.save_png(&path)?;
+        Ok(path)
+    }
+
+    // Empieza una toma nueva (recording/take_001, take_002, ...) o termina la actual.
+    // Devuelve el directorio de la toma que empezó o terminó.
+    pub fn toggle_recording(&mut self) -> io::Result<PathBuf> {
+        if let Some(take) = self.take.take() {
+            return Ok(take.dir);
+        }
+
+        let mut index = 1;
+        let dir = loop {
+            let dir = self.recording_dir.join(format!("take_{:03}", index));
+            if !dir.exists() {
+                break dir;
+            }
+            index += 1;
+        };
+
+        fs::create_dir_all(&dir)?;
+        self.take = Some(Take { dir: dir.clone(), frame: 0 });
+        Ok(dir)
+    }
+
+    pub fn record_frame(&mut self, framebuffer: &Framebuffer) -> io::Result<()> {
+        if let Some(take) = &mut self.take {
+            framebuffer.save_png(take.dir.join(format!("frame_{:05}.png", take.frame)))?;
+            take.frame += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use nalgebra_glm::Vec4;
+use crate::vertex::Vertex;
+
+// Planos del frustum en espacio de recorte (convención OpenGL, -w <= x, y, z <= w).
+// Un punto está dentro del plano p si dot(p, clip_position) >= 0.
+const FRUSTUM_PLANES: [Vec4; 6] = [
+    Vec4::new(0.0, 0.0, 1.0, 1.0),  // cercano: z >= -w
+    Vec4::new(0.0, 0.0, -1.0, 1.0), // lejano: z <= w
+    Vec4::new(1.0, 0.0, 0.0, 1.0),  // izquierdo: x >= -w
+    Vec4::new(-1.0, 0.0, 0.0, 1.0), // derecho: x <= w
+    Vec4::new(0.0, 1.0, 0.0, 1.0),  // inferior: y >= -w
+    Vec4::new(0.0, -1.0, 0.0, 1.0), // superior: y <= w
+];
+
+fn distance(plane: &Vec4, vertex: &Vertex) -> f32 {
+    plane.dot(&vertex.clip_position)
+}
+
+// Recorta un triángulo contra los seis planos del frustum (Sutherland-Hodgman)
+// y devuelve el resultado ya triangulado en abanico. Si queda fuera, la lista
+// está vacía; si queda completamente dentro, se devuelve tal cual.
+pub fn clip_triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<[Vertex; 3]> {
+    let mut fully_inside = true;
+    for plane in &FRUSTUM_PLANES {
+        let d = [distance(plane, v1), distance(plane, v2), distance(plane, v3)];
+        if d.iter().all(|&d| d < 0.0) {
+            return Vec::new();
+        }
+        if d.iter().any(|&d| d < 0.0) {
+            fully_inside = false;
+        }
+    }
+
+    if fully_inside {
+        return vec![[v1.clone(), v2.clone(), v3.clone()]];
+    }
+
+    let mut polygon = vec![v1.clone(), v2.clone(), v3.clone()];
+    for plane in &FRUSTUM_PLANES {
+        polygon = clip_polygon(&polygon, plane);
+        if polygon.len() < 3 {
+            return Vec::new();
+        }
+    }
+
+    (1..polygon.len() - 1)
+        .map(|i| [polygon[0].clone(), polygon[i].clone(), polygon[i + 1].clone()])
+        .collect()
+}
+
+fn clip_polygon(polygon: &[Vertex], plane: &Vec4) -> Vec<Vertex> {
+    let mut output = Vec::with_capacity(polygon.len() + 1);
+
+    for i in 0..polygon.len() {
+        let current = &polygon[i];
+        let next = &polygon[(i + 1) % polygon.len()];
+        let d_current = distance(plane, current);
+        let d_next = distance(plane, next);
+
+        if d_current >= 0.0 {
+            output.push(current.clone());
+        }
+
+        // La arista cruza el plano: agregamos la intersección
+        if (d_current >= 0.0) != (d_next >= 0.0) {
+            let t = d_current / (d_current - d_next);
+            output.push(current.lerp(next, t));
+        }
+    }
+
+    output
+}
+
+// Recorta un segmento contra el frustum (Liang-Barsky en coordenadas
+// homogéneas). Devuelve None si queda completamente fuera.
+pub fn clip_line(v1: &Vertex, v2: &Vertex) -> Option<(Vertex, Vertex)> {
+    let (mut t_start, mut t_end) = (0.0_f32, 1.0_f32);
+
+    for plane in &FRUSTUM_PLANES {
+        let d1 = distance(plane, v1);
+        let d2 = distance(plane, v2);
+
+        if d1 < 0.0 && d2 < 0.0 {
+            return None;
+        }
+        let t = d1 / (d1 - d2);
+        if d1 < 0.0 {
+            t_start = t_start.max(t);
+        } else if d2 < 0.0 {
+            t_end = t_end.min(t);
+        }
+    }
+
+    if t_start > t_end {
+        return None;
+    }
+
+    Some((v1.lerp(v2, t_start), v1.lerp(v2, t_end)))
+}
@@ -0,0 +1,308 @@
+use crate::units::DAY_SECONDS;
+
+// Multiplicadores de velocidad permitidos (segundos de simulación por segundo real)
+pub const MIN_SPEED: f64 = 1.0;
+pub const MAX_SPEED: f64 = 1.0e6;
+// Cuánto cambia la velocidad con cada pulsación
+const SPEED_FACTOR: f64 = 10.0;
+
+// Reloj de la simulación, separado del tiempo real: avanza según el tiempo
+// transcurrido entre cuadros multiplicado por la velocidad, puede ir hacia
+// atrás, pausarse o avanzar de a un paso. El tiempo son los segundos desde
+// `epoch`, que lo ubica en el calendario.
+#[derive(Debug, Clone)]
+pub struct SimClock {
+    pub time: f64,
+    pub speed: f64,
+    pub reversed: bool,
+    pub paused: bool,
+    pub epoch: f64, // Día juliano que corresponde a time = 0
+    pending_step: bool,
+}
+
+impl SimClock {
+    pub fn new(epoch: f64, speed: f64) -> Self {
+        SimClock {
+            time: 0.0,
+            speed: speed.clamp(MIN_SPEED, MAX_SPEED),
+            reversed: false,
+            paused: false,
+            epoch,
+            pending_step: false,
+        }
+    }
+
+    // Avanza el reloj por `real_dt` segundos reales y devuelve cuánto cambió el
+    // tiempo de la simulación (negativo si va hacia atrás, cero en pausa)
+    pub fn advance(&mut self, real_dt: f64) -> f64 {
+        if self.paused && !self.pending_step {
+            return 0.0;
+        }
+        self.pending_step = false;
+
+        let delta = real_dt * self.rate();
+        self.time += delta;
+        delta
+    }
+
+    // Segundos de simulación por segundo real, con signo
+    pub fn rate(&self) -> f64 {
+        if self.reversed { -self.speed } else { self.speed }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn toggle_reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * SPEED_FACTOR).min(MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / SPEED_FACTOR).max(MIN_SPEED);
+    }
+
+    // Pausa el reloj y deja que el próximo `advance` avance un solo cuadro
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.pending_step = true;
+    }
+
+    // Fecha actual como día juliano
+    pub fn date(&self) -> f64 {
+        self.epoch + self.time / DAY_SECONDS
+    }
+
+    pub fn jump_to_date(&mut self, julian_day: f64) {
+        self.time = (julian_day - self.epoch) * DAY_SECONDS;
+    }
+
+    // Resumen para la consola: "100x, hacia atrás, en pausa - 2000-01-05 12:00"
+    pub fn describe(&self) -> String {
+        let mut state = format!("{}x", self.speed);
+        if self.reversed {
+            state.push_str(", hacia atrás");
+        }
+        if self.paused {
+            state.push_str(", en pausa");
+        }
+        format!("{} - {}", state, format_date(self.date()))
+    }
+}
+
+// Fecha del calendario gregoriano a día juliano. Acepta "AAAA-MM-DD",
+// "AAAA-MM-DD HH:MM" y "AAAA-MM-DDTHH:MM[:SS]" (tiempo universal). Antes de
+// 1582 se sigue usando el gregoriano (proléptico), con año 0 = 1 a. C. y
+// años negativos: las tablas de JPL Horizons de esas fechas usan el juliano.
+pub fn parse_date(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (date, clock) = match text.split_once(['T', ' ']) {
+        Some((date, clock)) => (date, Some(clock)),
+        None => (text, None),
+    };
+
+    // El año puede ser negativo
+    let (sign, date) = match date.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, date),
+    };
+    let mut fields = date.split('-');
+    let year = sign * fields.next()?.parse::<i64>().ok()?;
+    let month = fields.next()?.parse::<i64>().ok()?;
+    let day = fields.next()?.parse::<i64>().ok()?;
+    if fields.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut seconds = 0.0;
+    if let Some(clock) = clock {
+        let mut fields = clock.split(':');
+        let hours = fields.next()?.parse::<f64>().ok()?;
+        let minutes = fields.next()?.parse::<f64>().ok()?;
+        let secs = fields.next().map_or(Some(0.0), |field| field.parse::<f64>().ok())?;
+        let valid = (0.0..24.0).contains(&hours) && (0.0..60.0).contains(&minutes) && (0.0..60.0).contains(&secs);
+        if fields.next().is_some() || !valid {
+            return None;
+        }
+        seconds = hours * 3600.0 + minutes * 60.0 + secs;
+    }
+
+    // Rechaza días que no existen (31 de abril, 29 de febrero en años no bisiestos)
+    let day_number = julian_day_number(year, month, day);
+    if civil_date(day_number) != (year, month, day) {
+        return None;
+    }
+
+    // El día juliano empieza al mediodía
+    Some(day_number as f64 - 0.5 + seconds / 86400.0)
+}
+
+// Día juliano a "AAAA-MM-DD HH:MM"
+pub fn format_date(julian_day: f64) -> String {
+    let shifted = julian_day + 0.5;
+    let mut day_number = shifted.floor() as i64;
+    let mut minutes = ((shifted - day_number as f64) * 1440.0).round() as i64;
+    if minutes >= 1440 {
+        day_number += 1;
+        minutes -= 1440;
+    }
+
+    let (year, month, day) = civil_date(day_number);
+    let sign = if year < 0 { "-" } else { "" };
+    format!("{}{:04}-{:02}-{:02} {:02}:{:02}", sign, year.abs(), month, day, minutes / 60, minutes % 60)
+}
+
+// Número de día juliano (a las 12:00) de una fecha gregoriana
+fn julian_day_number(year: i64, month: i64, day: i64) -> i64 {
+    let a = (14 - month) / 12;
+    let y = year + 4800 - a;
+    let m = month + 12 * a - 3;
+    day + (153 * m + 2) / 5 + 365 * y + y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400) - 32045
+}
+
+fn civil_date(day_number: i64) -> (i64, i64, i64) {
+    let a = day_number + 32044;
+    let b = (4 * a + 3).div_euclid(146097);
+    let c = a - (146097 * b).div_euclid(4);
+    let d = (4 * c + 3) / 1461;
+    let e = c - 1461 * d / 4;
+    let m = (5 * e + 2) / 153;
+
+    let day = e - (153 * m + 2) / 5 + 1;
+    let month = m + 3 - 12 * (m / 10);
+    let year = 100 * b + d - 4800 + m / 10;
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> f64 {
+        parse_date(text).unwrap_or_else(|| panic!("fecha inválida: {}", text))
+    }
+
+    #[test]
+    fn parses_reference_dates() {
+        // J2000 y el origen del día juliano (gregoriano proléptico)
+        assert_eq!(date("2000-01-01T12:00"), 2451545.0);
+        assert_eq!(date("2000-01-01 12:00"), 2451545.0);
+        assert_eq!(date("2000-01-01"), 2451544.5);
+        assert_eq!(date("-4713-11-24T12:00"), 0.0);
+        assert!((date("1969-07-20T20:17:40") - 2440423.345601852).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for text in [
+            "", "2000", "2000-01", "2000-13-01", "2000-00-10", "2000-01-32", "2000-04-31", "2001-02-29",
+            "1900-02-29", "2000-01-01-01", "2000-01-01T24:00", "2000-01-01T12:60", "2000-01-01T12",
+            "2000-01-01T12:00:00:00", "enero", "2000/01/01",
+        ] {
+            assert_eq!(parse_date(text), None, "{}", text);
+        }
+        assert!(parse_date("2000-02-29").is_some());
+        assert!(parse_date("1600-02-29").is_some());
+    }
+
+    #[test]
+    fn dates_round_trip() {
+        for text in [
+            "2000-01-01 12:00", "1582-10-04 00:00", "1582-10-15 00:00", "0001-01-01 06:30", "0000-03-01 00:00",
+            "-0044-03-15 12:00", "-4713-11-24 12:00", "2024-02-29 23:59", "9999-12-31 18:45",
+        ] {
+            assert_eq!(format_date(date(text)), text);
+        }
+
+        // Del año -332 al 489, cruzando el año 0, y alrededor del día juliano 0
+        for julian_day in (1_600_000..1_900_000).step_by(7).chain(-20_000..20_000) {
+            let julian_day = julian_day as f64 - 0.5;
+            assert_eq!(date(&format_date(julian_day)), julian_day, "día {}", julian_day);
+        }
+    }
+
+    #[test]
+    fn format_rounds_to_the_minute() {
+        assert_eq!(format_date(date("2000-01-01T23:59:59.9")), "2000-01-02 00:00");
+        assert_eq!(format_date(date("2000-12-31T23:59:40")), "2001-01-01 00:00");
+        assert_eq!(format_date(date("2000-01-01T10:15:20")), "2000-01-01 10:15");
+    }
+
+    #[test]
+    fn advances_by_speed() {
+        let mut clock = SimClock::new(2451545.0, 100.0);
+        assert_eq!(clock.advance(0.5), 50.0);
+        assert_eq!(clock.time, 50.0);
+
+        clock.jump_to_date(2451546.0);
+        assert_eq!(clock.time, DAY_SECONDS);
+        assert_eq!(clock.date(), 2451546.0);
+    }
+
+    #[test]
+    fn pause_and_single_step() {
+        let mut clock = SimClock::new(0.0, 10.0);
+        clock.toggle_pause();
+        assert_eq!(clock.advance(1.0), 0.0);
+
+        // Un paso avanza un solo cuadro y deja el reloj en pausa
+        clock.step();
+        assert_eq!(clock.advance(0.1), 1.0);
+        assert_eq!(clock.advance(0.1), 0.0);
+        assert!(clock.paused);
+
+        clock.toggle_pause();
+        assert_eq!(clock.advance(1.0), 10.0);
+        assert_eq!(clock.time, 11.0);
+
+        // Pedir un paso sin estar en pausa también pausa
+        clock.step();
+        assert_eq!(clock.advance(1.0), 10.0);
+        assert_eq!(clock.advance(1.0), 0.0);
+    }
+
+    #[test]
+    fn reverse_runs_backwards() {
+        let mut clock = SimClock::new(0.0, 1000.0);
+        clock.toggle_reverse();
+        assert_eq!(clock.rate(), -1000.0);
+        assert_eq!(clock.advance(2.0), -2000.0);
+        assert_eq!(clock.time, -2000.0);
+
+        // Un paso hacia atrás también retrocede
+        clock.step();
+        assert_eq!(clock.advance(1.0), -1000.0);
+
+        clock.toggle_reverse();
+        assert_eq!(clock.rate(), 1000.0);
+    }
+
+    #[test]
+    fn speed_is_clamped() {
+        let mut clock = SimClock::new(0.0, MAX_SPEED * 10.0);
+        assert_eq!(clock.speed, MAX_SPEED);
+        clock.faster();
+        assert_eq!(clock.speed, MAX_SPEED);
+
+        let mut clock = SimClock::new(0.0, 0.0);
+        assert_eq!(clock.speed, MIN_SPEED);
+        clock.slower();
+        assert_eq!(clock.speed, MIN_SPEED);
+        clock.faster();
+        assert_eq!(clock.speed, MIN_SPEED * SPEED_FACTOR);
+        clock.slower();
+        assert_eq!(clock.speed, MIN_SPEED);
+    }
+
+    #[test]
+    fn describes_state() {
+        let mut clock = SimClock::new(date("2000-01-05T12:00"), 100.0);
+        clock.toggle_reverse();
+        clock.toggle_pause();
+        assert_eq!(clock.describe(), "100x, hacia atrás, en pausa - 2000-01-05 12:00");
+    }
+}
@@ -1,75 +1,119 @@
 use std::fmt;
 
-#[derive(Debug, Clone, Copy)]
+// Color RGB lineal en punto flotante. Los canales pueden pasar de 1.0 (por
+// ejemplo, la superficie del Sol); el tone mapping los lleva a [0, 1] sólo al
+// presentar la imagen. `a` es la opacidad (1 = opaco), sin premultiplicar.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
-    r: u8,
-    g: u8,
-    b: u8,
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
 }
 
 impl Color {
-    pub const fn new(r: u8, g: u8, b: u8) -> Self {
-        Color { r, g, b }
+    // Canales de 8 bits en sRGB, como los de un selector de color o una imagen
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Color { r: srgb_to_linear(r), g: srgb_to_linear(g), b: srgb_to_linear(b), a: 1.0 }
     }
 
-    pub const fn from_hex(hex: u32) -> Self {
-        let r = ((hex >> 16) & 0xFF) as u8;
-        let g = ((hex >> 8) & 0xFF) as u8;
-        let b = (hex & 0xFF) as u8;
-        Color { r, g, b }
+    // Canales ya lineales
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Color { r, g, b, a: 1.0 }
+    }
+
+    pub const fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Color { r, g, b, a }
+    }
+
+    pub const fn with_alpha(self, a: f32) -> Self {
+        Color { a, ..self }
+    }
+
+    // 0xRRGGBB en sRGB
+    pub fn from_hex(hex: u32) -> Self {
+        Color::new((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
     }
 
     pub const fn black() -> Self {
-        Color { r: 0, g: 0, b: 0 }
+        Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }
+    }
+
+    // Codifica a 0xRRGGBB en sRGB, recortando lo que pase de 1.0
+    pub fn to_hex(self) -> u32 {
+        ((linear_to_srgb(self.r) as u32) << 16) | ((linear_to_srgb(self.g) as u32) << 8) | (linear_to_srgb(self.b) as u32)
     }
 
-    pub fn to_hex(&self) -> u32 {
-        ((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
+    pub fn luminance(&self) -> f32 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
     }
 
     // Linear interpolation between two colors
     pub fn lerp(&self, other: &Color, t: f32) -> Self {
         let t = t.clamp(0.0, 1.0);
         Color {
-            r: (self.r as f32 + (other.r as f32 - self.r as f32) * t).round() as u8,
-            g: (self.g as f32 + (other.g as f32 - self.g as f32) * t).round() as u8,
-            b: (self.b as f32 + (other.b as f32 - self.b as f32) * t).round() as u8,
+            r: self.r + (other.r - self.r) * t,
+            g: self.g + (other.g - self.g) * t,
+            b: self.b + (other.b - self.b) * t,
+            a: self.a + (other.a - self.a) * t,
         }
     }
 
     pub fn is_black(&self) -> bool {
-        self.r == 0 && self.g == 0 && self.b == 0 
+        self.r == 0.0 && self.g == 0.0 && self.b == 0.0
     }
 
+    // Los blend_* combinan el color de destino (self) con el que llega
+    // (blend) y conservan la opacidad del destino; `over` aplica la opacidad
+    // del que llega sobre el resultado.
     pub fn blend_normal(&self, blend: &Color) -> Color {
-        if blend.is_black() { *self } else { *blend }
+        Color { a: self.a, ..*blend }
       }
-    
+
     pub fn blend_multiply(&self, blend: &Color) -> Color {
-        Color::new(
-            ((self.r as f32 * blend.r as f32) / 255.0) as u8,
-            ((self.g as f32 * blend.g as f32) / 255.0) as u8,
-            ((self.b as f32 * blend.b as f32) / 255.0) as u8
-        )
+        Color::rgba(self.r * blend.r, self.g * blend.g, self.b * blend.b, self.a)
     }
-    
+
     pub fn blend_add(&self, blend: &Color) -> Color {
-        Color::new(
-            (self.r as u16 + blend.r as u16).min(255) as u8,
-            (self.g as u16 + blend.g as u16).min(255) as u8,
-            (self.b as u16 + blend.b as u16).min(255) as u8
-        )
+        *self + *blend
     }
-    
+
     pub fn blend_subtract(&self, blend: &Color) -> Color {
-        let r = (self.r as i16 - blend.r as i16).max(0).min(255) as u8;
-        let g = (self.g as i16 - blend.g as i16).max(0).min(255) as u8;
-        let b = (self.b as i16 - blend.b as i16).max(0).min(255) as u8;
+        Color::rgba(
+            (self.r - blend.r).max(0.0),
+            (self.g - blend.g).max(0.0),
+            (self.b - blend.b).max(0.0),
+            self.a,
+        )
+    }
 
-        Color::new(r, g, b)
+    // Mezcla `blended` (resultado de un blend_*) sobre self según la opacidad de `source`
+    pub fn over(&self, blended: &Color, source: &Color) -> Color {
+        let t = source.a.clamp(0.0, 1.0);
+        Color { a: self.a, ..self.lerp(blended, t) }
     }
 }
 
+// Curva de transferencia sRGB (IEC 61966-2-1)
+fn srgb_to_linear(value: u8) -> f32 {
+    let v = value as f32 / 255.0;
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let v = value.clamp(0.0, 1.0);
+    let encoded = if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
+
 use std::ops::Add;
 
 impl Add for Color {
@@ -77,9 +121,10 @@ impl Add for Color {
 
     fn add(self, other: Color) -> Color {
         Color {
-            r: self.r.saturating_add(other.r),
-            g: self.g.saturating_add(other.g),
-            b: self.b.saturating_add(other.b),
+            r: self.r + other.r,
+            g: self.g + other.g,
+            b: self.b + other.b,
+            a: self.a,
         }
     }
 }
@@ -91,15 +136,50 @@ impl Mul<f32> for Color {
 
     fn mul(self, scalar: f32) -> Color {
         Color {
-            r: (self.r as f32 * scalar).clamp(0.0, 255.0) as u8,
-            g: (self.g as f32 * scalar).clamp(0.0, 255.0) as u8,
-            b: (self.b as f32 * scalar).clamp(0.0, 255.0) as u8,
+            r: (self.r * scalar).max(0.0),
+            g: (self.g * scalar).max(0.0),
+            b: (self.b * scalar).max(0.0),
+            a: self.a,
         }
     }
 }
 
 impl fmt::Display for Color {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Color(r: {}, g: {}, b: {})", self.r, self.g, self.b)
+        write!(f, "Color(r: {}, g: {}, b: {}, a: {})", self.r, self.g, self.b, self.a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb_round_trips_every_value() {
+        for value in 0..=255u8 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(value)), value);
+        }
+    }
+
+    #[test]
+    fn srgb_to_linear_is_monotonic() {
+        assert_eq!(srgb_to_linear(0), 0.0);
+        assert_eq!(srgb_to_linear(255), 1.0);
+        for value in 1..=255u8 {
+            assert!(srgb_to_linear(value) > srgb_to_linear(value - 1), "{}", value);
+        }
+    }
+
+    #[test]
+    fn linear_to_srgb_clamps() {
+        assert_eq!(linear_to_srgb(-0.5), 0);
+        assert_eq!(linear_to_srgb(3.0), 255);
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        for hex in [0x000000, 0xFFFFFF, 0x060611, 0x4682B4, 0xFF9A40] {
+            assert_eq!(Color::from_hex(hex).to_hex(), hex);
+        }
     }
 }
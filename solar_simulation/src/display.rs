@@ -0,0 +1,364 @@
+use nalgebra_glm::Vec3;
+
+use crate::planet::Planet;
+
+// Duración de la transición animada entre modos, en segundos reales
+const TRANSITION_SECONDS: f32 = 1.5;
+// En la escala comprimida las distancias crecen con el logaritmo a partir de
+// esta fracción de la distancia más lejana
+const COMPRESSION_REFERENCE: f64 = 1.0 / 40.0;
+// Los radios comprimidos crecen como r^RADIUS_EXPONENT
+const RADIUS_EXPONENT: f64 = 0.3;
+// Radio comprimido del cuerpo más grande, como fracción de la distancia
+// comprimida de la órbita más cercana
+const LARGEST_RADIUS: f64 = 0.25;
+// Separación de las lunas comprimidas: radios del padre por cada factor e de distancia
+const MOON_SPREAD: f64 = 0.5;
+
+// Cómo se lleva el sistema físico (km) a la escena dibujada
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleMode {
+    True,        // Distancias y radios reales
+    Exaggerated, // Distancias reales, radios agrandados
+    Compressed,  // Distancias logarítmicas y radios comprimidos
+}
+
+impl ScaleMode {
+    pub fn next(self) -> Self {
+        match self {
+            ScaleMode::True => ScaleMode::Exaggerated,
+            ScaleMode::Exaggerated => ScaleMode::Compressed,
+            ScaleMode::Compressed => ScaleMode::True,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScaleMode::True => "true",
+            ScaleMode::Exaggerated => "exaggerated",
+            ScaleMode::Compressed => "compressed",
+        }
+    }
+
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "true" => Some(ScaleMode::True),
+            "exaggerated" => Some(ScaleMode::Exaggerated),
+            "compressed" => Some(ScaleMode::Compressed),
+            _ => None,
+        }
+    }
+}
+
+// Capa de escala de pantalla. Cada cuerpo se ubica respecto a su padre ya
+// dibujado: en la escala real, a su distancia dividida por `unit`; con los
+// radios exagerados, las lunas se corren hacia afuera lo que crecieron los
+// radios para no quedar dentro de su planeta; en la comprimida, las órbitas
+// alrededor del centro crecen con el logaritmo de la distancia (conservando
+// la más lejana) y las lunas con el logaritmo de su distancia en radios del
+// padre. Al cambiar de modo, posiciones y radios pasan de uno a otro en
+// TRANSITION_SECONDS.
+#[derive(Debug, Clone)]
+pub struct DisplayScale {
+    pub mode: ScaleMode,
+    pub unit: f64,         // km por unidad de la escena dibujada
+    pub exaggeration: f64, // Factor de los radios en el modo exaggerated (las estrellas, su raíz)
+    pub farthest: f64,     // Distancia (km) que la escala comprimida conserva
+    pub radius_scale: f64, // Radio comprimido de un cuerpo de 1 km
+    previous: ScaleMode,
+    transition: f32, // De 0 (recién cambiado) a 1 (terminado)
+}
+
+impl DisplayScale {
+    // Calibra la escala comprimida con las órbitas de los cuerpos sin padre
+    pub fn new(mode: ScaleMode, unit: f64, exaggeration: f64, planets: &[Planet]) -> Self {
+        let extents: Vec<(f64, f64)> = planets
+            .iter()
+            .filter(|planet| planet.parent.is_none())
+            .filter_map(|planet| match &planet.ephemeris {
+                Some(ephemeris) => {
+                    let distances = ephemeris.samples.iter().map(|sample| sample.position.norm());
+                    let (near, far) = distances.fold((f64::MAX, 0.0_f64), |(near, far), d| (near.min(d), far.max(d)));
+                    Some((near, far))
+                }
+                None if planet.orbit.semi_major_axis > 0.0 => {
+                    let (a, e) = (planet.orbit.semi_major_axis as f64, planet.orbit.eccentricity as f64);
+                    Some((a * (1.0 - e), a * (1.0 + e)))
+                }
+                None => None,
+            })
+            .filter(|&(near, _)| near > 0.0)
+            .collect();
+
+        let farthest = extents.iter().map(|&(_, far)| far).fold(0.0, f64::max);
+        let farthest = if farthest > 0.0 { farthest } else { unit };
+        let nearest = extents.iter().map(|&(near, _)| near).fold(farthest, f64::min);
+        let largest = planets.iter().map(|planet| planet.radius as f64).fold(1.0, f64::max);
+
+        let mut scale = DisplayScale {
+            mode,
+            unit,
+            exaggeration,
+            farthest,
+            radius_scale: 1.0,
+            previous: mode,
+            transition: 1.0,
+        };
+        scale.radius_scale = LARGEST_RADIUS * scale.compress(nearest) / largest.powf(RADIUS_EXPONENT);
+        scale
+    }
+
+    pub fn set_mode(&mut self, mode: ScaleMode) {
+        self.previous = self.mode;
+        self.mode = mode;
+        self.transition = 0.0;
+    }
+
+    pub fn update(&mut self, real_dt: f32) {
+        self.transition = (self.transition + real_dt / TRANSITION_SECONDS).min(1.0);
+    }
+
+    // Posición y radio dibujados de todos los cuerpos (los padres van antes)
+    pub fn apply(&self, planets: &mut [Planet]) {
+        for index in 0..planets.len() {
+            let offset = match planets[index].parent {
+                Some(parent) => planets[index].position - planets[parent].position,
+                None => planets[index].position,
+            };
+            let position = self.place(planets, index, offset);
+            let radius = self.blend(|mode| self.radius_in(mode, &planets[index]).ln()).exp();
+
+            let planet = &mut planets[index];
+            planet.display_position = position;
+            planet.display_radius = radius as f32;
+        }
+    }
+
+    // Lleva un desplazamiento físico (km) respecto al padre del cuerpo
+    // `index` a la escena dibujada. Sirve para el cuerpo y para su órbita.
+    pub fn place(&self, planets: &[Planet], index: usize, offset: Vec3) -> Vec3 {
+        let origin = planets[index].parent.map_or(Vec3::zeros(), |parent| planets[parent].display_position);
+        let distance = offset.magnitude() as f64;
+        if distance <= 0.0 {
+            return origin;
+        }
+
+        let displayed = self.blend(|mode| self.distance_in(mode, planets, index, distance));
+        origin + offset * (displayed / distance) as f32
+    }
+
+    // Radio de una esfera alrededor del origen que contiene a todos los
+    // cuerpos dibujados y sus órbitas completas (después de `apply`)
+    pub fn extent(&self, planets: &[Planet]) -> f32 {
+        let mut extent = 0.0_f32;
+        for (index, planet) in planets.iter().enumerate() {
+            let origin = planet.parent.map_or(Vec3::zeros(), |parent| planets[parent].display_position);
+            let offset = match planet.parent {
+                Some(parent) => planet.position - planets[parent].position,
+                None => planet.position,
+            };
+            // Lo más lejos que llega del padre: apoapsis, muestra más lejana
+            // de las efemérides o dónde está ahora (offset, N-cuerpos)
+            let farthest = match &planet.ephemeris {
+                Some(ephemeris) => ephemeris.samples.iter().map(|sample| sample.position.norm()).fold(0.0, f64::max) as f32,
+                None => planet.orbit.semi_major_axis * (1.0 + planet.orbit.eccentricity),
+            };
+            let reach = farthest.max(offset.magnitude());
+
+            let displayed = (self.place(planets, index, Vec3::x() * reach) - origin).magnitude();
+            extent = extent.max(origin.magnitude() + displayed + planet.display_radius);
+        }
+        extent
+    }
+
+    // Valor de la transición en curso: mezcla del modo anterior y el actual
+    fn blend(&self, value: impl Fn(ScaleMode) -> f64) -> f64 {
+        let current = value(self.mode);
+        if self.transition >= 1.0 {
+            return current;
+        }
+
+        let t = self.transition as f64;
+        let t = t * t * (3.0 - 2.0 * t);
+        value(self.previous) + (current - value(self.previous)) * t
+    }
+
+    fn radius_in(&self, mode: ScaleMode, planet: &Planet) -> f64 {
+        let radius = planet.radius as f64;
+        match mode {
+            ScaleMode::True => radius / self.unit,
+            ScaleMode::Exaggerated if planet.luminosity > 0.0 => radius / self.unit * self.exaggeration.sqrt(),
+            ScaleMode::Exaggerated => radius / self.unit * self.exaggeration,
+            ScaleMode::Compressed => self.radius_scale * radius.powf(RADIUS_EXPONENT),
+        }
+    }
+
+    // Distancia dibujada al padre (o al centro) de un cuerpo a `distance` km
+    fn distance_in(&self, mode: ScaleMode, planets: &[Planet], index: usize, distance: f64) -> f64 {
+        let planet = &planets[index];
+        match (mode, planet.parent) {
+            (ScaleMode::True, _) | (ScaleMode::Exaggerated, None) => distance / self.unit,
+            (ScaleMode::Exaggerated, Some(parent)) => {
+                let growth = |body: &Planet| self.radius_in(mode, body) - body.radius as f64 / self.unit;
+                distance / self.unit + growth(&planets[parent]) + growth(planet)
+            }
+            (ScaleMode::Compressed, None) => self.compress(distance),
+            (ScaleMode::Compressed, Some(parent)) => {
+                let parent = &planets[parent];
+                let radii = (distance / parent.radius as f64).max(1.0);
+                self.radius_in(mode, parent) * (1.0 + MOON_SPREAD * radii.ln()) + self.radius_in(mode, planet)
+            }
+        }
+    }
+
+    fn compress(&self, distance: f64) -> f64 {
+        let reference = self.farthest * COMPRESSION_REFERENCE;
+        self.farthest / self.unit * (1.0 + distance / reference).ln() / (1.0 + 1.0 / COMPRESSION_REFERENCE).ln()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planet::update_positions;
+    use crate::scene::Scene;
+
+    const MODES: [ScaleMode; 3] = [ScaleMode::True, ScaleMode::Exaggerated, ScaleMode::Compressed];
+
+    fn solar_system() -> Scene {
+        let mut scene = Scene::load("assets/scenes/solar_system.toml").unwrap();
+        update_positions(&mut scene.bodies, scene.clock.time);
+        scene
+    }
+
+    fn index(scene: &Scene, name: &str) -> usize {
+        scene.bodies.iter().position(|planet| planet.name == name).unwrap()
+    }
+
+    #[test]
+    fn transitions_follow_a_smoothstep() {
+        let scene = solar_system();
+        let mut display = DisplayScale::new(ScaleMode::True, scene.display.unit, scene.display.exaggeration, &scene.bodies);
+        display.set_mode(ScaleMode::Compressed);
+        let value = |mode| if mode == ScaleMode::True { 0.0 } else { 1.0 };
+
+        // Arranca en el modo anterior, con pendiente nula
+        assert_eq!(display.blend(value), 0.0);
+        display.update(TRANSITION_SECONDS * 0.01);
+        assert!(display.blend(value) < 1e-3);
+
+        let mut last = display.blend(value);
+        for _ in 0..98 {
+            display.update(TRANSITION_SECONDS * 0.01);
+            let blended = display.blend(value);
+            assert!(blended > last, "{blended} después de {last}");
+            last = blended;
+        }
+        // Llega al modo nuevo también con pendiente nula y ahí se queda
+        assert!(last > 1.0 - 1e-3);
+        display.update(TRANSITION_SECONDS);
+        assert_eq!(display.blend(value), 1.0);
+    }
+
+    #[test]
+    fn transitions_are_halfway_at_half_time() {
+        let scene = solar_system();
+        let mut display = DisplayScale::new(ScaleMode::True, scene.display.unit, scene.display.exaggeration, &scene.bodies);
+        display.set_mode(ScaleMode::Exaggerated);
+        display.update(TRANSITION_SECONDS * 0.5);
+        let value = |mode| if mode == ScaleMode::True { 2.0 } else { 4.0 };
+        assert!((display.blend(value) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn distances_keep_their_order() {
+        let scene = solar_system();
+        let (earth, moon) = (index(&scene, "Tierra"), index(&scene, "Luna"));
+        for mode in MODES {
+            let mut planets = solar_system().bodies;
+            let display = DisplayScale::new(mode, scene.display.unit, scene.display.exaggeration, &planets);
+            display.apply(&mut planets);
+
+            for body in [earth, moon] {
+                // Desde más allá del radio de la Tierra: más cerca, la escala
+                // comprimida pega las lunas a la superficie del padre
+                let mut last = 0.0;
+                for exponent in 0..40 {
+                    let distance = 10.0_f32.powf(4.0 + exponent as f32 * 0.15);
+                    let origin = planets[body].parent.map_or(Vec3::zeros(), |parent| planets[parent].display_position);
+                    let displayed = (display.place(&planets, body, Vec3::x() * distance) - origin).magnitude();
+                    assert!(displayed > last, "{}: {} km a {displayed}", mode.name(), distance);
+                    last = displayed;
+                }
+            }
+
+            // Los planetas siguen ordenados por distancia al Sol
+            let mut roots: Vec<&Planet> = planets.iter().filter(|planet| planet.parent.is_none()).collect();
+            roots.sort_by(|a, b| a.position.magnitude().total_cmp(&b.position.magnitude()));
+            for pair in roots.windows(2) {
+                assert!(
+                    pair[0].display_position.magnitude() <= pair[1].display_position.magnitude(),
+                    "{}: {} y {}",
+                    mode.name(),
+                    pair[0].name,
+                    pair[1].name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn moons_stay_outside_their_parent() {
+        let scene = solar_system();
+        for mode in MODES {
+            let mut planets = solar_system().bodies;
+            let display = DisplayScale::new(mode, scene.display.unit, scene.display.exaggeration, &planets);
+            display.apply(&mut planets);
+
+            for moon in planets.iter().filter(|planet| planet.parent.is_some()) {
+                let parent = &planets[moon.parent.unwrap()];
+                let separation = (moon.display_position - parent.display_position).magnitude();
+                assert!(
+                    separation > parent.display_radius + moon.display_radius,
+                    "{}: {} dentro de {}",
+                    mode.name(),
+                    moon.name,
+                    parent.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn exaggerated_moons_move_out_by_the_radius_growth() {
+        let scene = solar_system();
+        let (earth, moon) = (index(&scene, "Tierra"), index(&scene, "Luna"));
+        let mut real = solar_system().bodies;
+        let mut exaggerated = solar_system().bodies;
+        DisplayScale::new(ScaleMode::True, scene.display.unit, scene.display.exaggeration, &real).apply(&mut real);
+        DisplayScale::new(ScaleMode::Exaggerated, scene.display.unit, scene.display.exaggeration, &exaggerated)
+            .apply(&mut exaggerated);
+
+        let separation = |planets: &[Planet]| (planets[moon].display_position - planets[earth].display_position).magnitude();
+        let radii = |planets: &[Planet]| planets[earth].display_radius + planets[moon].display_radius;
+        // La superficie de la Luna queda a la misma distancia de la de la Tierra
+        let gap = separation(&real) - radii(&real);
+        let exaggerated_gap = separation(&exaggerated) - radii(&exaggerated);
+        assert!((gap - exaggerated_gap).abs() < gap * 1e-4, "{gap} contra {exaggerated_gap}");
+    }
+
+    #[test]
+    fn extent_contains_every_body() {
+        let scene = solar_system();
+        for mode in MODES {
+            let mut planets = solar_system().bodies;
+            let display = DisplayScale::new(mode, scene.display.unit, scene.display.exaggeration, &planets);
+            display.apply(&mut planets);
+
+            let extent = display.extent(&planets);
+            for planet in &planets {
+                assert!(planet.display_position.magnitude() + planet.display_radius <= extent, "{}", planet.name);
+            }
+        }
+    }
+}
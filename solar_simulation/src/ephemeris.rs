@@ -0,0 +1,441 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use nalgebra_glm::{DVec3, Vec3};
+
+use crate::clock;
+use crate::units::{AU_KM, DAY_SECONDS};
+
+// Una muestra de la tabla: instante, posición y velocidad
+#[derive(Debug, Clone, Copy)]
+pub struct EphemerisSample {
+    pub time: f64,
+    pub position: DVec3,
+    pub velocity: DVec3,
+}
+
+// Efemérides de un cuerpo leídas de un archivo: muestras ordenadas en el
+// tiempo, interpoladas con Hermite cúbico (posición y velocidad en cada
+// extremo). Al cargarse están en días julianos, UA y UA/día; la escena las
+// pasa a las unidades de la simulación con `convert`.
+#[derive(Debug, Clone)]
+pub struct Ephemeris {
+    pub samples: Vec<EphemerisSample>,
+}
+
+#[derive(Debug)]
+pub enum EphemerisError {
+    Io(std::io::Error),
+    Parse(usize, String), // Línea (desde 1) y descripción
+    Empty,
+}
+
+impl fmt::Display for EphemerisError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EphemerisError::Io(err) => write!(f, "{}", err),
+            EphemerisError::Parse(line, message) => write!(f, "línea {}: {}", line, message),
+            EphemerisError::Empty => write!(f, "el archivo no tiene muestras"),
+        }
+    }
+}
+
+impl std::error::Error for EphemerisError {}
+
+impl Ephemeris {
+    // Lee una tabla de vectores exportada de JPL Horizons (texto o CSV, con
+    // las marcas $$SOE / $$EOE) o un CSV simple: epoch, x, y, z, vx, vy, vz
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, EphemerisError> {
+        let text = fs::read_to_string(path).map_err(EphemerisError::Io)?;
+        if text.contains("$$SOE") {
+            Self::parse_horizons(&text)
+        } else {
+            Self::parse_csv(&text)
+        }
+    }
+
+    // Tabla de vectores de Horizons (EPHEM_TYPE = VECTORS, VEC_TABLE 2 o 3).
+    // Las unidades salen de la línea "Output units" (KM-S si no está).
+    pub fn parse_horizons(text: &str) -> Result<Self, EphemerisError> {
+        let units_line = text
+            .lines()
+            .enumerate()
+            .find_map(|(index, line)| Some((index + 1, line.trim_start().strip_prefix("Output units")?)));
+        let (length_scale, time_scale) = match units_line {
+            Some((number, rest)) => rest
+                .trim_start()
+                .strip_prefix(':')
+                .and_then(horizons_units)
+                .ok_or_else(|| EphemerisError::Parse(number, "unidades desconocidas (se esperaba AU-D, KM-S o KM-D)".to_string()))?,
+            None => (1.0 / AU_KM, DAY_SECONDS),
+        };
+        let velocity_scale = length_scale * time_scale;
+
+        let mut samples = Vec::new();
+        let mut record: Option<(usize, f64, String)> = None;
+        let mut inside = false;
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let trimmed = line.trim();
+            if trimmed.starts_with("$$SOE") {
+                inside = true;
+                continue;
+            }
+            if trimmed.starts_with("$$EOE") {
+                break;
+            }
+            if !inside || trimmed.is_empty() {
+                continue;
+            }
+
+            if trimmed.contains(',') {
+                // Formato CSV: JDTDB, fecha, X, Y, Z, VX, VY, VZ, ...
+                let fields: Vec<&str> = trimmed.split(',').map(str::trim).collect();
+                let values: Option<Vec<f64>> = fields.iter().skip(2).take(6).map(|field| field.parse().ok()).collect();
+                let (Some(time), Some(values)) = (fields[0].parse::<f64>().ok(), values) else {
+                    return Err(EphemerisError::Parse(number, "se esperaba JDTDB, fecha, X, Y, Z, VX, VY, VZ".to_string()));
+                };
+                if values.len() < 6 {
+                    return Err(EphemerisError::Parse(number, "faltan las velocidades (use VEC_TABLE = 2)".to_string()));
+                }
+                samples.push(EphemerisSample {
+                    time,
+                    position: DVec3::new(values[0], values[1], values[2]) * length_scale,
+                    velocity: DVec3::new(values[3], values[4], values[5]) * velocity_scale,
+                });
+            } else if let Some((time, _)) = trimmed.split_once('=').and_then(|(jd, rest)| Some((jd.trim().parse::<f64>().ok()?, rest))) {
+                // Formato de texto: "2451545.0 = A.D. ..." y después X =, Y =, ..., VZ=
+                if let Some(done) = record.take() {
+                    samples.push(horizons_record(done, length_scale, velocity_scale)?);
+                }
+                record = Some((number, time, String::new()));
+            } else if let Some((_, _, fields)) = record.as_mut() {
+                fields.push(' ');
+                fields.push_str(trimmed);
+            }
+        }
+        if let Some(done) = record.take() {
+            samples.push(horizons_record(done, length_scale, velocity_scale)?);
+        }
+
+        Self::from_samples(samples)
+    }
+
+    // CSV simple, una muestra por línea: epoch, x, y, z, vx, vy, vz. La época
+    // es un día juliano o una fecha AAAA-MM-DD[THH:MM]; posiciones en UA y
+    // velocidades en UA/día. Se ignoran las líneas vacías, las que empiezan
+    // con # y un encabezado en la primera línea con datos (puede haber
+    // comentarios antes).
+    pub fn parse_csv(text: &str) -> Result<Self, EphemerisError> {
+        let mut samples = Vec::new();
+        let mut seen_data = false;
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let first = !seen_data;
+            seen_data = true;
+
+            let fields: Vec<&str> = trimmed.split(',').map(str::trim).collect();
+            let epoch = fields[0].parse::<f64>().ok().or_else(|| clock::parse_date(fields[0]));
+            let Some(time) = epoch else {
+                if first {
+                    continue; // Encabezado
+                }
+                return Err(EphemerisError::Parse(number, format!("época inválida '{}'", fields[0])));
+            };
+
+            let values: Option<Vec<f64>> = fields[1..].iter().map(|field| field.parse().ok()).collect();
+            match values {
+                Some(values) if values.len() == 6 => samples.push(EphemerisSample {
+                    time,
+                    position: DVec3::new(values[0], values[1], values[2]),
+                    velocity: DVec3::new(values[3], values[4], values[5]),
+                }),
+                _ => return Err(EphemerisError::Parse(number, "se esperaba epoch, x, y, z, vx, vy, vz".to_string())),
+            }
+        }
+
+        Self::from_samples(samples)
+    }
+
+    fn from_samples(mut samples: Vec<EphemerisSample>) -> Result<Self, EphemerisError> {
+        if samples.is_empty() {
+            return Err(EphemerisError::Empty);
+        }
+        samples.sort_by(|a, b| a.time.total_cmp(&b.time));
+        samples.dedup_by(|a, b| a.time == b.time);
+        Ok(Ephemeris { samples })
+    }
+
+    // Pasa a las unidades de la simulación: segundos desde `epoch` (día
+    // juliano), km y km/s
+    pub fn convert(&mut self, epoch: f64) {
+        for sample in &mut self.samples {
+            sample.time = (sample.time - epoch) * DAY_SECONDS;
+            sample.position *= AU_KM;
+            sample.velocity *= AU_KM / DAY_SECONDS;
+        }
+    }
+
+    pub fn start(&self) -> f64 {
+        self.samples[0].time
+    }
+
+    pub fn end(&self) -> f64 {
+        self.samples[self.samples.len() - 1].time
+    }
+
+    // Posición y velocidad en `time`. Fuera del intervalo de la tabla el
+    // cuerpo se dibuja en la primera o la última muestra, pero conserva la
+    // velocidad de esa muestra: el modo N-cuerpos parte de ella.
+    pub fn state_at(&self, time: f64) -> (Vec3, Vec3) {
+        let next = self.samples.partition_point(|sample| sample.time <= time);
+        if next == 0 || next == self.samples.len() {
+            let sample = &self.samples[next.saturating_sub(1)];
+            return (to_vec3(&sample.position), to_vec3(&sample.velocity));
+        }
+
+        let (a, b) = (&self.samples[next - 1], &self.samples[next]);
+        let h = b.time - a.time;
+        let s = (time - a.time) / h;
+        let (s2, s3) = (s * s, s * s * s);
+
+        // Bases de Hermite y sus derivadas respecto a s
+        let position = a.position * (2.0 * s3 - 3.0 * s2 + 1.0)
+            + a.velocity * (h * (s3 - 2.0 * s2 + s))
+            + b.position * (-2.0 * s3 + 3.0 * s2)
+            + b.velocity * (h * (s3 - s2));
+        let velocity = a.position * ((6.0 * s2 - 6.0 * s) / h)
+            + a.velocity * (3.0 * s2 - 4.0 * s + 1.0)
+            + b.position * ((-6.0 * s2 + 6.0 * s) / h)
+            + b.velocity * (3.0 * s2 - 2.0 * s);
+
+        (to_vec3(&position), to_vec3(&velocity))
+    }
+}
+
+// Factores a UA y a días para las unidades de Horizons
+fn horizons_units(text: &str) -> Option<(f64, f64)> {
+    match text.trim().split([',', ' ']).next()? {
+        "AU-D" => Some((1.0, 1.0)),
+        "KM-S" => Some((1.0 / AU_KM, DAY_SECONDS)),
+        "KM-D" => Some((1.0 / AU_KM, 1.0)),
+        _ => None,
+    }
+}
+
+fn horizons_record(
+    (number, time, fields): (usize, f64, String),
+    length_scale: f64,
+    velocity_scale: f64,
+) -> Result<EphemerisSample, EphemerisError> {
+    let value = |key: &str| horizons_field(&fields, key);
+    match (value("X"), value("Y"), value("Z"), value("VX"), value("VY"), value("VZ")) {
+        (Some(x), Some(y), Some(z), Some(vx), Some(vy), Some(vz)) => Ok(EphemerisSample {
+            time,
+            position: DVec3::new(x, y, z) * length_scale,
+            velocity: DVec3::new(vx, vy, vz) * velocity_scale,
+        }),
+        _ => Err(EphemerisError::Parse(number, "se esperaban X, Y, Z, VX, VY y VZ (use VEC_TABLE = 2)".to_string())),
+    }
+}
+
+// Valor de "KEY = número" dentro de un registro; la clave tiene que empezar
+// una palabra, así "X" no encuentra el de "VX"
+fn horizons_field(fields: &str, key: &str) -> Option<f64> {
+    let mut rest = fields;
+    while let Some(found) = rest.find(key) {
+        let before = &rest[..found];
+        let after = rest[found + key.len()..].trim_start();
+        rest = &rest[found + key.len()..];
+
+        if !before.ends_with(|c: char| c.is_whitespace()) && !before.is_empty() {
+            continue;
+        }
+        if let Some(value) = after.strip_prefix('=') {
+            return value.split_whitespace().next()?.parse().ok();
+        }
+    }
+    None
+}
+
+fn to_vec3(v: &DVec3) -> Vec3 {
+    Vec3::new(v.x as f32, v.y as f32, v.z as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HORIZONS: &str = "tests/fixtures/horizons_circular.txt";
+    const CSV: &str = "tests/fixtures/circular.csv";
+    const COMMENTED_CSV: &str = "tests/fixtures/circular_commented.csv";
+    const J2000: f64 = 2451545.0;
+    // Las tablas de prueba son una órbita circular de 1 UA y 365.25 días
+    const PERIOD_DAYS: f64 = 365.25;
+
+    fn circular_state(julian_day: f64) -> (DVec3, DVec3) {
+        let rate = std::f64::consts::TAU / PERIOD_DAYS;
+        let (sin, cos) = ((julian_day - J2000) * rate).sin_cos();
+        (DVec3::new(cos, sin, 0.0), DVec3::new(-sin, cos, 0.0) * rate)
+    }
+
+    fn assert_close(value: DVec3, expected: DVec3, tolerance: f64) {
+        assert!((value - expected).norm() <= tolerance, "{:?} no es {:?}", value, expected);
+    }
+
+    fn assert_close_f32(value: Vec3, expected: DVec3, relative: f32) {
+        let expected = to_vec3(&expected);
+        let tolerance = expected.magnitude() * relative;
+        assert!((value - expected).magnitude() <= tolerance, "{:?} no es {:?}", value, expected);
+    }
+
+    #[test]
+    fn parses_horizons_text_tables() {
+        let ephemeris = Ephemeris::load(HORIZONS).unwrap();
+        assert_eq!(ephemeris.samples.len(), 5);
+
+        // KM-S pasa a UA y UA/día
+        for (index, sample) in ephemeris.samples.iter().enumerate() {
+            assert_eq!(sample.time, J2000 + 10.0 * index as f64);
+            let (position, velocity) = circular_state(sample.time);
+            assert_close(sample.position, position, 1e-12);
+            assert_close(sample.velocity, velocity, 1e-12);
+        }
+    }
+
+    #[test]
+    fn parses_csv_tables_with_header_and_dates() {
+        let csv = Ephemeris::load(CSV).unwrap();
+        let horizons = Ephemeris::load(HORIZONS).unwrap();
+        assert_eq!(csv.samples.len(), horizons.samples.len());
+
+        for (a, b) in csv.samples.iter().zip(&horizons.samples) {
+            assert_eq!(a.time, b.time);
+            assert_close(a.position, b.position, 1e-12);
+            assert_close(a.velocity, b.velocity, 1e-12);
+        }
+    }
+
+    #[test]
+    fn skips_a_header_after_leading_comments() {
+        let commented = Ephemeris::load(COMMENTED_CSV).unwrap();
+        let csv = Ephemeris::load(CSV).unwrap();
+        assert_eq!(commented.samples.len(), csv.samples.len());
+        for (a, b) in commented.samples.iter().zip(&csv.samples) {
+            assert_eq!(a.time, b.time);
+            assert_eq!(a.position, b.position);
+        }
+
+        // Sólo se salta la primera línea con datos: un segundo encabezado es un error
+        let text = "# fuente\nepoch, x, y, z, vx, vy, vz\nepoch, x, y, z, vx, vy, vz\n2451545.0, 1, 0, 0, 0, 1, 0\n";
+        assert!(matches!(Ephemeris::parse_csv(text), Err(EphemerisError::Parse(3, _))));
+    }
+
+    #[test]
+    fn parses_horizons_csv_tables() {
+        let text = "Output units    : AU-D\n$$SOE\n\
+            2451545.0, A.D. 2000-Jan-01 12:00:00.0000, 1.0, 2.0, 3.0, 0.1, 0.2, 0.3,\n\
+            2451546.0, A.D. 2000-Jan-02 12:00:00.0000, 1.1, 2.2, 3.3, 0.1, 0.2, 0.3,\n\
+            $$EOE\n";
+        let ephemeris = Ephemeris::parse_horizons(text).unwrap();
+        assert_eq!(ephemeris.samples.len(), 2);
+        assert_eq!(ephemeris.samples[1].time, 2451546.0);
+        assert_close(ephemeris.samples[1].position, DVec3::new(1.1, 2.2, 3.3), 1e-12);
+        assert_close(ephemeris.samples[1].velocity, DVec3::new(0.1, 0.2, 0.3), 1e-12);
+    }
+
+    #[test]
+    fn converts_horizons_units() {
+        let record = "$$SOE\n2451545.0 = A.D. 2000-Jan-01\n X = 1.0 Y = 2.0 Z = 3.0\n VX= 4.0 VY= 5.0 VZ= 6.0\n$$EOE\n";
+        let sample = |units: &str| Ephemeris::parse_horizons(&format!("{}{}", units, record)).unwrap().samples[0];
+
+        let au_day = sample("Output units    : AU-D\n");
+        assert_close(au_day.position, DVec3::new(1.0, 2.0, 3.0), 1e-12);
+        assert_close(au_day.velocity, DVec3::new(4.0, 5.0, 6.0), 1e-12);
+
+        let km_day = sample("Output units    : KM-D\n");
+        assert_close(km_day.position * AU_KM, DVec3::new(1.0, 2.0, 3.0), 1e-9);
+        assert_close(km_day.velocity * AU_KM, DVec3::new(4.0, 5.0, 6.0), 1e-9);
+
+        // Sin la línea de unidades, Horizons usa KM-S
+        let km_second = sample("");
+        assert_close(km_second.velocity * AU_KM / DAY_SECONDS, DVec3::new(4.0, 5.0, 6.0), 1e-9);
+
+        let unknown = Ephemeris::parse_horizons(&format!("\nOutput units : PC-Y\n{}", record));
+        assert!(matches!(unknown, Err(EphemerisError::Parse(2, _))));
+    }
+
+    #[test]
+    fn reports_malformed_tables() {
+        let missing_velocity = "$$SOE\n2451545.0 = A.D. 2000-Jan-01\n X = 1.0 Y = 2.0 Z = 3.0\n$$EOE\n";
+        assert!(matches!(Ephemeris::parse_horizons(missing_velocity), Err(EphemerisError::Parse(2, _))));
+        assert!(matches!(Ephemeris::parse_horizons("$$SOE\n$$EOE\n"), Err(EphemerisError::Empty)));
+
+        let bad_epoch = "2451545.0, 1, 0, 0, 0, 0, 0\nayer, 1, 0, 0, 0, 0, 0\n";
+        assert!(matches!(Ephemeris::parse_csv(bad_epoch), Err(EphemerisError::Parse(2, _))));
+        let short_line = "2451545.0, 1, 0, 0, 0, 0\n";
+        assert!(matches!(Ephemeris::parse_csv(short_line), Err(EphemerisError::Parse(1, _))));
+        assert!(matches!(Ephemeris::parse_csv("epoch, x, y, z, vx, vy, vz\n"), Err(EphemerisError::Empty)));
+    }
+
+    #[test]
+    fn sorts_and_deduplicates_samples() {
+        let text = "2451547.0, 3, 0, 0, 0, 0, 0\n2451545.0, 1, 0, 0, 0, 0, 0\n2451547.0, 9, 0, 0, 0, 0, 0\n";
+        let ephemeris = Ephemeris::parse_csv(text).unwrap();
+        let times: Vec<f64> = ephemeris.samples.iter().map(|sample| sample.time).collect();
+        assert_eq!(times, [2451545.0, 2451547.0]);
+    }
+
+    fn converted() -> Ephemeris {
+        let mut ephemeris = Ephemeris::load(HORIZONS).unwrap();
+        ephemeris.convert(J2000);
+        ephemeris
+    }
+
+    #[test]
+    fn interpolation_passes_through_samples() {
+        let ephemeris = converted();
+        assert_eq!(ephemeris.start(), 0.0);
+        assert_eq!(ephemeris.end(), 40.0 * DAY_SECONDS);
+
+        for sample in &ephemeris.samples {
+            let (position, velocity) = ephemeris.state_at(sample.time);
+            assert_close_f32(position, sample.position, 1e-6);
+            assert_close_f32(velocity, sample.velocity, 1e-6);
+        }
+    }
+
+    #[test]
+    fn interpolation_follows_the_orbit_between_samples() {
+        let ephemeris = converted();
+        for step in 0..80 {
+            let days = step as f64 * 0.5 + 0.25;
+            let (position, velocity) = ephemeris.state_at(days * DAY_SECONDS);
+            let (expected_position, expected_velocity) = circular_state(J2000 + days);
+            // Error de Hermite con muestras cada 10 días: ~(ωh)⁴/384 ≈ 2e-6
+            assert_close_f32(position, expected_position * AU_KM, 5e-6);
+            assert_close_f32(velocity, expected_velocity * (AU_KM / DAY_SECONDS), 1e-4);
+        }
+    }
+
+    #[test]
+    fn clamps_outside_the_table_keeping_the_velocity() {
+        let ephemeris = converted();
+        let (first, last) = (&ephemeris.samples[0], &ephemeris.samples[ephemeris.samples.len() - 1]);
+
+        let (position, velocity) = ephemeris.state_at(-1e9);
+        assert_close_f32(position, first.position, 1e-6);
+        assert_close_f32(velocity, first.velocity, 1e-6);
+
+        let (position, velocity) = ephemeris.state_at(ephemeris.end() + 1e9);
+        assert_close_f32(position, last.position, 1e-6);
+        assert_close_f32(velocity, last.velocity, 1e-6);
+    }
+}
@@ -1,12 +1,63 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 
+use rayon::prelude::*;
+
+use crate::color::Color;
+use crate::tonemap::ToneMapping;
+
+// Cómo se combina un color nuevo con el que ya está en el framebuffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    Opaque,   // Reemplaza el color y escribe la profundidad
+    Alpha,    // Mezcla según la opacidad del color nuevo
+    Additive, // Suma (brillos, estelas de motor)
+    Multiply,
+    Subtract,
+}
+
+impl BlendMode {
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "opaque" => Some(BlendMode::Opaque),
+            "alpha" => Some(BlendMode::Alpha),
+            "additive" => Some(BlendMode::Additive),
+            "multiply" => Some(BlendMode::Multiply),
+            "subtract" => Some(BlendMode::Subtract),
+            _ => None,
+        }
+    }
+
+    pub fn is_transparent(self) -> bool {
+        self != BlendMode::Opaque
+    }
+
+    // Color resultante de dibujar `source` sobre `destination`
+    pub fn apply(self, destination: Color, source: Color) -> Color {
+        let blended = match self {
+            BlendMode::Opaque => return source,
+            BlendMode::Alpha => destination.blend_normal(&source),
+            BlendMode::Additive => destination.blend_add(&source),
+            BlendMode::Multiply => destination.blend_multiply(&source),
+            BlendMode::Subtract => destination.blend_subtract(&source),
+        };
+        destination.over(&blended, &source)
+    }
+}
+
+// Se dibuja en `hdr` (color lineal sin límite); `resolve` aplica el tone
+// mapping y deja en `buffer` los píxeles 0xRRGGBB en sRGB que se presentan
 pub struct Framebuffer {
     pub width: usize,
     pub height: usize,
     pub buffer: Vec<u32>,
+    pub hdr: Vec<Color>,
     pub zbuffer: Vec<f32>,
-    pub emissive_buffer: Vec<u32>, // Nuevo buffer emisivo
+    pub emissive_buffer: Vec<Color>, // Luz emitida por cada píxel (para el bloom)
+    pub tone_mapping: ToneMapping,
     background_color: u32,
-    current_color: u32,
+    current_color: Color,
 }
 
 impl Framebuffer {
@@ -15,55 +66,25 @@ impl Framebuffer {
             width,
             height,
             buffer: vec![0; width * height],
+            hdr: vec![Color::black(); width * height],
             zbuffer: vec![f32::INFINITY; width * height],
-            emissive_buffer: vec![0; width * height], // Inicializar con negro (sin emisión)
+            emissive_buffer: vec![Color::black(); width * height], // Inicializar con negro (sin emisión)
+            tone_mapping: ToneMapping::default(),
             background_color: 0x000000,
-            current_color: 0xFFFFFF,
+            current_color: Color::rgb(1.0, 1.0, 1.0),
         }
     }
 
     pub fn clear(&mut self) {
-        for pixel in self.buffer.iter_mut() {
-            *pixel = self.background_color;
+        let background = Color::from_hex(self.background_color);
+        for pixel in self.hdr.iter_mut() {
+            *pixel = background;
         }
         for depth in self.zbuffer.iter_mut() {
             *depth = f32::INFINITY;
         }
         for emissive_pixel in self.emissive_buffer.iter_mut() {
-            *emissive_pixel = 0; // Limpiar el buffer emisivo a negro
-        }
-    }
-
-    pub fn draw_line(&mut self, x1: usize, y1: usize, x2: usize, y2: usize, color: u32) {
-        let dx = (x2 as isize - x1 as isize).abs();
-        let dy = (y2 as isize - y1 as isize).abs();
-        let sx = if x1 < x2 { 1 } else { -1 };
-        let sy = if y1 < y2 { 1 } else { -1 };
-
-        let mut err = dx - dy;
-
-        let mut x = x1 as isize;
-        let mut y = y1 as isize;
-
-        loop {
-            if x >= 0 && x < self.width as isize && y >= 0 && y < self.height as isize {
-                let index = y as usize * self.width + x as usize;
-                self.buffer[index] = color;
-            }
-
-            if x == x2 as isize && y == y2 as isize {
-                break;
-            }
-
-            let e2 = 2 * err;
-            if e2 > -dy {
-                err -= dy;
-                x += sx;
-            }
-            if e2 < dx {
-                err += dx;
-                y += sy;
-            }
+            *emissive_pixel = Color::black(); // Limpiar el buffer emisivo a negro
         }
     }
 
@@ -72,19 +93,23 @@ impl Framebuffer {
             let index = y * self.width + x;
 
             if self.zbuffer[index] > depth {
-                self.buffer[index] = self.current_color;
+                self.hdr[index] = self.current_color;
                 self.zbuffer[index] = depth;
             }
         }
     }
 
-    // Método para escribir en el buffer emisivo
-    pub fn emissive_point(&mut self, x: usize, y: usize, color: u32, depth: f32) {
+    // Como `point`, pero combina el color con `mode`. Las superficies
+    // transparentes se prueban contra la profundidad sin escribirla.
+    pub fn blend_point(&mut self, x: usize, y: usize, depth: f32, color: Color, mode: BlendMode) {
         if x < self.width && y < self.height {
             let index = y * self.width + x;
 
             if self.zbuffer[index] > depth {
-                self.emissive_buffer[index] = color; // Guardar en el buffer emisivo
+                self.hdr[index] = mode.apply(self.hdr[index], color);
+                if mode == BlendMode::Opaque {
+                    self.zbuffer[index] = depth;
+                }
             }
         }
     }
@@ -94,6 +119,55 @@ impl Framebuffer {
     }
 
     pub fn set_current_color(&mut self, color: u32) {
-        self.current_color = color;
+        self.current_color = Color::from_hex(color);
+    }
+
+    // Tone mapping y codificación sRGB de `hdr` a `buffer`
+    pub fn resolve(&mut self) {
+        let tone_mapping = self.tone_mapping;
+        self.buffer
+            .par_iter_mut()
+            .zip(&self.hdr)
+            .for_each(|(pixel, &color)| *pixel = tone_mapping.encode(color));
+    }
+
+    // Convierte el buffer (0xRRGGBB) a bytes RGB
+    fn to_rgb_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.buffer.len() * 3);
+        for &pixel in &self.buffer {
+            bytes.extend_from_slice(&[(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8]);
+        }
+        bytes
+    }
+
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        write!(file, "P6\n{} {}\n255\n", self.width, self.height)?;
+        file.write_all(&self.to_rgb_bytes())?;
+        file.flush()
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer.write_image_data(&self.to_rgb_bytes()).map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+
+    // Elige el formato según la extensión (.png o .ppm)
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ppm") => self.save_ppm(path),
+            Some("png") => self.save_png(path),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("formato de imagen no soportado: {}", path.display()),
+            )),
+        }
     }
 }
@@ -0,0 +1,268 @@
+use nalgebra_glm::{Vec3, Mat4, look_at, perspective};
+use std::f32::consts::PI;
+
+pub mod framebuffer;
+pub mod triangle;
+pub mod line;
+pub mod raster;
+pub mod clipping;
+pub mod vertex;
+pub mod obj;
+pub mod color;
+pub mod fragment;
+pub mod light;
+pub mod atmosphere;
+pub mod ring;
+pub mod shadow;
+pub mod bloom;
+pub mod tonemap;
+pub mod shaders;
+pub mod camera;
+pub mod clock;
+pub mod units;
+pub mod display;
+
+pub mod planet;
+pub mod ship;
+pub mod orbit;
+pub mod ephemeris;
+pub mod nbody;
+pub mod scene;
+pub mod backend;
+pub mod capture;
+pub mod texture;
+pub mod mesh;
+
+use color::Color;
+use framebuffer::{BlendMode, Framebuffer};
+use vertex::Vertex;
+use shaders::{ShaderFn, ShaderParams};
+use fastnoise_lite::{FastNoiseLite, NoiseType};
+use std::sync::Arc;
+use rayon::prelude::*;
+use texture::Texture;
+use light::Light;
+use shadow::ShadowMap;
+use atmosphere::Atmosphere;
+
+pub struct Uniforms {
+    pub model_matrix: Mat4,
+    pub view_matrix: Mat4,
+    pub projection_matrix: Mat4,
+    pub viewport_matrix: Mat4,
+    pub time: f32, // Días de simulación desde el arranque, para animar shaders (puede ser negativo)
+    pub noise: FastNoiseLite,
+    pub shader_params: ShaderParams,
+    pub texture: Option<Arc<Texture>>,
+    pub lights: Vec<Light>,
+    pub shadow_maps: Vec<ShadowMap>, // Uno por luz, en el mismo orden (vacío = sin sombras)
+    pub camera_position: Vec3,       // En coordenadas de mundo
+    pub atmosphere: Option<Atmosphere>, // La del cuerpo cuyo cascarón se está dibujando
+}
+
+pub fn create_noise() -> FastNoiseLite {
+    create_cloud_noise()
+}
+
+fn create_cloud_noise() -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(1337);
+    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+    noise
+}
+
+pub fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
+    let (sin_x, cos_x) = rotation.x.sin_cos();
+    let (sin_y, cos_y) = rotation.y.sin_cos();
+    let (sin_z, cos_z) = rotation.z.sin_cos();
+
+    let rotation_matrix_x = Mat4::new(
+        1.0,  0.0,    0.0,   0.0,
+        0.0,  cos_x, -sin_x, 0.0,
+        0.0,  sin_x,  cos_x, 0.0,
+        0.0,  0.0,    0.0,   1.0,
+    );
+
+    let rotation_matrix_y = Mat4::new(
+        cos_y,  0.0,  sin_y, 0.0,
+        0.0,    1.0,  0.0,   0.0,
+        -sin_y, 0.0,  cos_y, 0.0,
+        0.0,    0.0,  0.0,   1.0,
+    );
+
+    let rotation_matrix_z = Mat4::new(
+        cos_z, -sin_z, 0.0, 0.0,
+        sin_z,  cos_z, 0.0, 0.0,
+        0.0,    0.0,  1.0, 0.0,
+        0.0,    0.0,  0.0, 1.0,
+    );
+
+    let rotation_matrix = rotation_matrix_z * rotation_matrix_y * rotation_matrix_x;
+
+    let transform_matrix = Mat4::new(
+        scale, 0.0,   0.0,   translation.x,
+        0.0,   scale, 0.0,   translation.y,
+        0.0,   0.0,   scale, translation.z,
+        0.0,   0.0,   0.0,   1.0,
+    );
+
+    transform_matrix * rotation_matrix
+}
+
+
+pub fn create_view_matrix(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
+    look_at(&eye, &center, &up)
+}
+
+// Planos de recorte por defecto: el near deja ver la nave de cerca
+pub const NEAR_PLANE: f32 = 0.1;
+pub const FAR_PLANE: f32 = 1000.0;
+// Relación far/near máxima antes de que el z-buffer pierda precisión
+const MAX_DEPTH_RATIO: f32 = 1.0e6;
+
+pub fn create_perspective_matrix(window_width: f32, window_height: f32, near: f32, far: f32) -> Mat4 {
+    let fov = 45.0 * PI / 180.0;
+    let aspect_ratio = window_width / window_height;
+
+    perspective(fov, aspect_ratio, near, far)
+}
+
+// Planos de recorte para una cámara en `eye` y una escena dibujada que cabe
+// en una esfera de radio `extent` alrededor del origen: el far llega hasta el
+// otro lado de la escena, y el near sólo se aleja si la relación entre ambos
+// pasaría de MAX_DEPTH_RATIO
+pub fn clip_planes(eye: Vec3, extent: f32) -> (f32, f32) {
+    let far = ((eye.magnitude() + extent) * 1.1).max(FAR_PLANE);
+    (NEAR_PLANE.max(far / MAX_DEPTH_RATIO), far)
+}
+
+pub fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
+    Mat4::new(
+        width / 2.0, 0.0, 0.0, width / 2.0,
+        0.0, height / 2.0, 0.0, height / 2.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0
+    )
+}
+
+use rand::Rng;
+
+pub fn generate_skybox_stars(num_stars: usize, framebuffer_width: usize, framebuffer_height: usize) -> Vec<(usize, usize)> {
+    let mut rng = rand::thread_rng();
+    let mut stars = Vec::new();
+
+    for _ in 0..num_stars {
+        let x = rng.gen_range(0..framebuffer_width);
+        let y = rng.gen_range(0..framebuffer_height);
+        stars.push((x, y));
+    }
+
+    stars
+}
+
+// Las estrellas quedan en el fondo: se escriben en `hdr` sin tocar el
+// zbuffer, así cualquier cuerpo u órbita que se dibuje después las tapa
+pub fn render_skybox(framebuffer: &mut Framebuffer, stars: &Vec<(usize, usize)>, color: u32) {
+    let color = Color::from_hex(color);
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    let mut star = |x: usize, y: usize| {
+        if x < width && y < height {
+            framebuffer.hdr[y * width + x] = color;
+        }
+    };
+
+    for &(x, y) in stars {
+        // Centro de la estrella
+        star(x, y);
+
+        // Resplandor básico más pequeño; en el borde se omiten los vecinos
+        // de afuera (los que pasan del ancho o el alto se descartan)
+        star(x + 1, y); // Derecha
+        if let Some(left) = x.checked_sub(1) {
+            star(left, y); // Izquierda
+        }
+        star(x, y + 1); // Abajo
+        if let Some(up) = y.checked_sub(1) {
+            star(x, up); // Arriba
+        }
+    }
+}
+
+// Función general para renderizar un cuerpo celeste con su shader específico
+pub fn render_celestial_body(
+    framebuffer: &mut Framebuffer,
+    vertex_array: &[Vertex],
+    uniforms: &Uniforms,
+    fragment_shader: ShaderFn,
+) {
+    render_blended_body(framebuffer, vertex_array, uniforms, fragment_shader, BlendMode::Opaque);
+}
+
+// Igual que `render_celestial_body` pero combinando con `blend`. Los cuerpos
+// transparentes se dibujan después de todo lo opaco; sus triángulos se
+// ordenan de atrás hacia adelante para que la mezcla salga bien.
+pub fn render_blended_body(
+    framebuffer: &mut Framebuffer,
+    vertex_array: &[Vertex],
+    uniforms: &Uniforms,
+    fragment_shader: ShaderFn,
+    blend: BlendMode,
+) {
+    // Vertex Shader, recorte contra el frustum y proyección a pantalla
+    let mut triangles: Vec<[Vertex; 3]> = vertex_array
+        .par_chunks_exact(3)
+        .flat_map_iter(|tri| {
+            let v1 = shaders::vertex_shader(&tri[0], uniforms);
+            let v2 = shaders::vertex_shader(&tri[1], uniforms);
+            let v3 = shaders::vertex_shader(&tri[2], uniforms);
+
+            let mut clipped = clipping::clip_triangle(&v1, &v2, &v3);
+            for tri in clipped.iter_mut() {
+                for vertex in tri.iter_mut() {
+                    shaders::project_to_screen(vertex, uniforms);
+                }
+            }
+            clipped
+        })
+        .collect();
+
+    if blend.is_transparent() {
+        let depth = |tri: &[Vertex; 3]| tri.iter().map(|v| v.transformed_position.z).sum::<f32>();
+        triangles.sort_by(|a, b| depth(b).total_cmp(&depth(a)));
+    }
+
+    // Rasterización por tiles y Fragment Shader específico para cada planeta
+    raster::draw_triangles(framebuffer, &triangles, uniforms, fragment_shader, blend);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clip_planes_cover_the_scene() {
+        // Escena chica: los planos por defecto alcanzan
+        assert_eq!(clip_planes(Vec3::new(0.0, 0.0, 10.0), 50.0), (NEAR_PLANE, FAR_PLANE));
+
+        // Escena grande: far llega al borde opuesto y near sube con él
+        let (near, far) = clip_planes(Vec3::new(0.0, 3.0e4, 4.0e4), 1.0e5);
+        assert!(far >= 5.0e4 + 1.0e5);
+        assert!(near > NEAR_PLANE);
+        assert!(far / near <= MAX_DEPTH_RATIO * 1.0001);
+    }
+
+    #[test]
+    fn skybox_stars_on_the_border_stay_inside() {
+        let mut framebuffer = Framebuffer::new(4, 3);
+        framebuffer.clear();
+        let stars = vec![(0, 0), (3, 2), (0, 2), (3, 0)];
+        render_skybox(&mut framebuffer, &stars, 0xFFFFFF);
+
+        let color_at = |x: usize, y: usize| framebuffer.hdr[y * framebuffer.width + x];
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (3, 2), (2, 2), (3, 1)] {
+            assert_eq!(color_at(x, y), Color::from_hex(0xFFFFFF), "({}, {})", x, y);
+        }
+        assert_eq!(color_at(1, 1), Color::black());
+        // Las estrellas no escriben profundidad
+        assert!(framebuffer.zbuffer.iter().all(|depth| *depth == f32::INFINITY));
+    }
+}
@@ -0,0 +1,42 @@
+use nalgebra_glm::Vec3;
+
+use crate::fragment::Fragment;
+use crate::Uniforms;
+
+// Luz puntual en espacio de mundo (por ejemplo, una estrella de la escena),
+// ubicada donde se dibuja la estrella. No se atenúa con la distancia: la
+// escala comprimida cambia las distancias dibujadas y la iluminación de cada
+// cuerpo no debe depender del modo de escala.
+#[derive(Debug, Clone, Copy)]
+pub struct Light {
+    pub position: Vec3,
+    pub intensity: f32,
+}
+
+// Iluminación difusa (Lambert) de todas las luces sobre el fragmento, con un
+// mínimo `ambient` para que el lado nocturno no quede completamente negro.
+// Si la luz tiene mapa de sombras, sólo cuenta la fracción que no está tapada.
+pub fn diffuse(fragment: &Fragment, uniforms: &Uniforms, ambient: f32) -> f32 {
+    let normal = fragment.normal.normalize();
+
+    let lit: f32 = uniforms
+        .lights
+        .iter()
+        .enumerate()
+        .map(|(index, light)| {
+            let light_dir = (light.position - fragment.world_position).normalize();
+            let lambert = normal.dot(&light_dir).max(0.0);
+            if lambert == 0.0 {
+                return 0.0;
+            }
+
+            let visibility = uniforms
+                .shadow_maps
+                .get(index)
+                .map_or(1.0, |shadow_map| shadow_map.visibility(fragment.world_position, normal));
+            lambert * visibility * light.intensity
+        })
+        .sum();
+
+    lit.max(ambient)
+}
@@ -1,42 +1,99 @@
-use crate::fragment::Fragment;
-use crate::vertex::Vertex;
-use crate::color::Color;
+use nalgebra_glm::{Vec3, Vec4};
 
-pub fn line(a: &Vertex, b: &Vertex) -> Vec<Fragment> {
-    let mut fragments = Vec::new();
+use crate::clipping;
+use crate::framebuffer::{BlendMode, Framebuffer};
+use crate::shaders::project_to_screen;
+use crate::vertex::Vertex;
+use crate::Uniforms;
 
-    let start = a.transformed_position;
-    let end = b.transformed_position;
+// Línea entre dos vértices ya proyectados a pantalla (x, y en píxeles y la
+// profundidad en z), con antialiasing de Xiaolin Wu generalizado a cualquier
+// grosor: en cada paso del eje principal se cubre un tramo de `thickness`
+// píxeles (medido perpendicular a la línea) y cada píxel recibe la fracción
+// que le toca como opacidad. Color y profundidad se interpolan entre los
+// extremos. Se prueba contra el z-buffer sin escribirlo, así lo que ya está
+// dibujado delante la tapa.
+pub fn line(framebuffer: &mut Framebuffer, a: &Vertex, b: &Vertex, thickness: f32) {
+    let (start, end) = (a.transformed_position, b.transformed_position);
+    let steep = (end.y - start.y).abs() > (end.x - start.x).abs();
 
-    let mut x0 = start.x as i32;
-    let mut y0 = start.y as i32;
-    let x1 = end.x as i32;
-    let y1 = end.y as i32;
+    // Eje principal u y secundario v
+    let swap = |p: Vec3| if steep { Vec3::new(p.y, p.x, p.z) } else { p };
+    let (mut p0, mut p1) = (swap(start), swap(end));
+    let (mut c0, mut c1) = (a.color, b.color);
+    if p0.x > p1.x {
+        std::mem::swap(&mut p0, &mut p1);
+        std::mem::swap(&mut c0, &mut c1);
+    }
 
-    let dx = (x1 - x0).abs();
-    let dy = (y1 - y0).abs();
+    let length = p1.x - p0.x;
+    if !length.is_finite() || length <= f32::EPSILON {
+        return;
+    }
+    let gradient = (p1.y - p0.y) / length;
 
-    let sx = if x0 < x1 { 1 } else { -1 };
-    let sy = if y0 < y1 { 1 } else { -1 };
+    // Las líneas de menos de un píxel se dibujan de un píxel y más tenues
+    let width = thickness.max(1.0);
+    let faint = thickness.clamp(0.0, 1.0);
+    let half = 0.5 * width * (1.0 + gradient * gradient).sqrt();
 
-    let mut err = if dx > dy { dx / 2 } else { -dy / 2 };
+    let last_u = (p1.x.floor() as i64).min(major_limit(framebuffer, steep));
+    for u in (p0.x.floor() as i64).max(0)..=last_u {
+        // Los píxeles de los extremos están cubiertos sólo en parte
+        let along = overlap(u as f32, p0.x, p1.x);
+        if along <= 0.0 {
+            continue;
+        }
 
-    loop {
-        let z = start.z + (end.z - start.z) * (x0 - start.x as i32) as f32 / (end.x - start.x) as f32;
-        fragments.push(Fragment::new(x0 as f32, y0 as f32, Color::new(255, 255, 255), z));
+        let center = u as f32 + 0.5;
+        let t = ((center - p0.x) / length).clamp(0.0, 1.0);
+        let v = p0.y + gradient * (center - p0.x);
+        let depth = p0.z + (p1.z - p0.z) * t;
+        let color = c0.lerp(&c1, t);
 
-        if x0 == x1 && y0 == y1 { break; }
+        for pixel in ((v - half).floor() as i64).max(0)..=((v + half).floor() as i64) {
+            let coverage = overlap(pixel as f32, v - half, v + half) * along * faint;
+            if coverage <= 0.0 {
+                continue;
+            }
 
-        let e2 = err;
-        if e2 > -dx {
-            err -= dy;
-            x0 += sx;
+            let (x, y) = if steep { (pixel, u) } else { (u, pixel) };
+            framebuffer.blend_point(x as usize, y as usize, depth, color.with_alpha(color.a * coverage), BlendMode::Alpha);
         }
-        if e2 < dy {
-            err += dx;
-            y0 += sy;
+    }
+}
+
+// Polilínea en el mundo: cada punto lleva su posición en `world_position` y
+// su color. Se transforma con la cámara, se recorta contra el frustum y se
+// dibuja tramo por tramo. Sirve para órbitas, trayectorias, ejes o grillas.
+pub fn polyline(framebuffer: &mut Framebuffer, uniforms: &Uniforms, points: &[Vertex], thickness: f32) {
+    let view_projection = uniforms.projection_matrix * uniforms.view_matrix;
+    let transformed: Vec<Vertex> = points
+        .iter()
+        .map(|point| {
+            let world = point.world_position;
+            Vertex {
+                clip_position: view_projection * Vec4::new(world.x, world.y, world.z, 1.0),
+                ..point.clone()
+            }
+        })
+        .collect();
+
+    for segment in transformed.windows(2) {
+        if let Some((mut from, mut to)) = clipping::clip_line(&segment[0], &segment[1]) {
+            project_to_screen(&mut from, uniforms);
+            project_to_screen(&mut to, uniforms);
+            line(framebuffer, &from, &to, thickness);
         }
     }
+}
+
+// Último índice válido sobre el eje principal
+fn major_limit(framebuffer: &Framebuffer, steep: bool) -> i64 {
+    if steep { framebuffer.height as i64 - 1 } else { framebuffer.width as i64 - 1 }
+}
 
-    fragments
+// Cuánto del píxel [pixel, pixel + 1) cae dentro de [from, to]
+fn overlap(pixel: f32, from: f32, to: f32) -> f32 {
+    (to.min(pixel + 1.0) - from.max(pixel)).max(0.0)
 }
@@ -1,504 +1,531 @@
-use nalgebra_glm::{Vec3, Mat4, look_at, perspective};
-use minifb::{Key, Window, WindowOptions};
-use std::time::Duration;
+use nalgebra_glm::{Vec3, Mat4};
+use minifb::Key;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use std::f32::consts::PI;
-use crate::fragment::Fragment;
-use crate::color::Color;
-
-mod framebuffer;
-mod triangle;
-mod vertex;
-mod obj;
-mod color;
-mod fragment;
-mod shaders;
-mod camera;
-
-mod planet;
-mod orbit;
-use planet::Planet;
-use orbit::render_orbit;
-
-use framebuffer::Framebuffer;
-use vertex::Vertex;
-use obj::Obj;
-use camera::Camera;
-use triangle::triangle;
-use shaders::{vertex_shader, fragment_shader, moon_shader, meteor_shader, ringed_planet_shader, earth_shader, gas_giant_shader, rocky_planet_shader, star_shader };
-use fastnoise_lite::{FastNoiseLite, NoiseType, FractalType};
-
-pub struct Uniforms {
-    model_matrix: Mat4,
-    view_matrix: Mat4,
-    projection_matrix: Mat4,
-    viewport_matrix: Mat4,
-    time: u32,
-    noise: FastNoiseLite
-}
 
-fn create_noise() -> FastNoiseLite {
-    create_cloud_noise()
+use solar_simulation::backend::{Backend, HeadlessBackend, WindowBackend};
+use solar_simulation::bloom::apply_bloom;
+use solar_simulation::camera::Camera;
+use solar_simulation::capture::Capture;
+use solar_simulation::clock::{self, SimClock};
+use solar_simulation::display::DisplayScale;
+use solar_simulation::framebuffer::Framebuffer;
+use solar_simulation::mesh::uv_sphere;
+use solar_simulation::nbody::{Integrator, NBodySystem};
+use solar_simulation::obj::Obj;
+use solar_simulation::orbit::{render_orbit, render_trajectory};
+use solar_simulation::planet::{update_kinematic_positions, update_positions, update_rotations, Planet};
+use solar_simulation::scene::Scene;
+use solar_simulation::ship::ShipControls;
+use solar_simulation::shadow::{ShadowCaster, ShadowMap, SHADOW_MAP_SIZE};
+use solar_simulation::shaders::ShaderParams;
+use solar_simulation::units::DAY_SECONDS;
+use solar_simulation::{
+    clip_planes, create_noise, create_perspective_matrix, create_view_matrix, create_viewport_matrix,
+    generate_skybox_stars, render_skybox, Uniforms,
+};
+
+// Modo de simulación: órbitas keplerianas analíticas o gravedad mutua
+pub enum SimulationMode {
+    Kepler,
+    NBody(NBodySystem),
 }
 
-fn create_cloud_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(1337);
-    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
-    noise
-}
+// Subpaso máximo del integrador N-cuerpos (segundos) y tope de subpasos por
+// cuadro: a velocidades muy altas el paso crece y la deriva de energía lo refleja
+const NBODY_MAX_STEP: f64 = 600.0;
+const NBODY_MAX_SUBSTEPS: usize = 2000;
+// Sin ventana o grabando, el reloj avanza un paso fijo por cuadro: las
+// grabaciones se arman a 60 fps
+const FIXED_FRAME_TIME: f64 = 1.0 / 60.0;
+// Tope del tiempo real entre cuadros, para no saltar tras un cuadro muy lento
+const MAX_FRAME_TIME: f64 = 0.25;
+const DEFAULT_SCENE: &str = "assets/scenes/solar_system.toml";
 
-fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
-    let (sin_x, cos_x) = rotation.x.sin_cos();
-    let (sin_y, cos_y) = rotation.y.sin_cos();
-    let (sin_z, cos_z) = rotation.z.sin_cos();
-
-    let rotation_matrix_x = Mat4::new(
-        1.0,  0.0,    0.0,   0.0,
-        0.0,  cos_x, -sin_x, 0.0,
-        0.0,  sin_x,  cos_x, 0.0,
-        0.0,  0.0,    0.0,   1.0,
-    );
-
-    let rotation_matrix_y = Mat4::new(
-        cos_y,  0.0,  sin_y, 0.0,
-        0.0,    1.0,  0.0,   0.0,
-        -sin_y, 0.0,  cos_y, 0.0,
-        0.0,    0.0,  0.0,   1.0,
-    );
-
-    let rotation_matrix_z = Mat4::new(
-        cos_z, -sin_z, 0.0, 0.0,
-        sin_z,  cos_z, 0.0, 0.0,
-        0.0,    0.0,  1.0, 0.0,
-        0.0,    0.0,  0.0, 1.0,
-    );
-
-    let rotation_matrix = rotation_matrix_z * rotation_matrix_y * rotation_matrix_x;
-
-    let transform_matrix = Mat4::new(
-        scale, 0.0,   0.0,   translation.x,
-        0.0,   scale, 0.0,   translation.y,
-        0.0,   0.0,   scale, translation.z,
-        0.0,   0.0,   0.0,   1.0,
-    );
-
-    transform_matrix * rotation_matrix
-}
+fn main() {
+    let window_width = 800;
+    let window_height = 600;
+    let framebuffer_width = 800;
+    let framebuffer_height = 600;
+    let frame_delay = Duration::from_millis(16);
 
+    let options = parse_args();
 
-fn create_view_matrix(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
-    look_at(&eye, &center, &up)
-}
+    // Cargar la escena (planetas, cámara, fondo y skybox)
+    let scene = match Scene::load(&options.scene_path) {
+        Ok(scene) => scene,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
 
-fn create_perspective_matrix(window_width: f32, window_height: f32) -> Mat4 {
-    let fov = 45.0 * PI / 180.0;
-    let aspect_ratio = window_width / window_height;
-    let near = 0.1;
-    let far = 1000.0;
+    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
+    let mut backend: Box<dyn Backend> = if options.headless {
+        match HeadlessBackend::new(options.out_dir.clone(), &options.format, options.frames) {
+            Ok(backend) => Box::new(backend),
+            Err(err) => {
+                eprintln!("no se pudo crear '{}': {}", options.out_dir.display(), err);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        Box::new(WindowBackend::new("Sistema Solar Simulado", window_width, window_height, frame_delay))
+    };
 
-    perspective(fov, aspect_ratio, near, far)
-}
+    backend.set_title(&format!("Sistema Solar Simulado - {}", scene.name));
+    framebuffer.set_background_color(scene.background_color);
+    framebuffer.tone_mapping = scene.tone_mapping;
+
+    let mut camera = scene.camera;
+    let mut clock = scene.clock;
+    let mut display = scene.display;
+    let mut ship = scene.ship;
+    let mut chase = false; // Cámara de persecución (C) o libre
+    if let Some(date) = &options.date {
+        match clock::parse_date(date) {
+            Some(julian_day) => clock.jump_to_date(julian_day),
+            None => {
+                eprintln!("--date espera una fecha AAAA-MM-DD[THH:MM], se recibió '{}'", date);
+                std::process::exit(1);
+            }
+        }
+    }
+    let start_time = clock.time; // Home vuelve acá
+    let mut planets = scene.bodies;
 
-fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
-    Mat4::new(
-        width / 2.0, 0.0, 0.0, width / 2.0,
-        0.0, -height / 2.0, 0.0, height / 2.0,
-        0.0, 0.0, 1.0, 0.0,
-        0.0, 0.0, 0.0, 1.0
-    )
-}
+    // Vertex array de una esfera (modelo básico para todos los planetas)
+    let obj = Obj::load("assets/sphere-1.obj").expect("Failed to load obj");
+    let vertex_arrays = obj.get_vertex_array();
 
-use rand::Rng; // Necesitarás la crate `rand`
+    // sphere-1.obj no trae coordenadas de textura útiles: los cuerpos con
+    // textura usan una esfera UV generada
+    let textured_vertex_arrays = uv_sphere(24, 48);
 
-pub fn generate_skybox_stars(num_stars: usize, framebuffer_width: usize, framebuffer_height: usize) -> Vec<(usize, usize)> {
-    let mut rng = rand::thread_rng();
-    let mut stars = Vec::new();
+    let nave_obj = Obj::load("assets/nave.obj").expect("Failed to load nave.obj");
+    let nave_vertex_arrays = nave_obj.get_vertex_array();
 
-    for _ in 0..num_stars {
-        let x = rng.gen_range(0..framebuffer_width);
-        let y = rng.gen_range(0..framebuffer_height);
-        stars.push((x, y));
-    }
 
-    stars
-}
 
-pub fn render_nave(
-    framebuffer: &mut Framebuffer,
-    camera: &Camera,
-    vertex_array: &[Vertex],
-    time: i32,
-) {
-    let nave_translation: Vec3 = camera.eye + camera.forward() * 5.0 + Vec3::new(-0.9, -0.9, 0.0);
-    let nave_scale = 0.1; // Tamaño de la nave
-    let nave_model_matrix = create_model_matrix(
-        nave_translation,
-        nave_scale,
-        Vec3::new(0.0, 1.57, 0.0) // Rotación de 90 grados en el eje Y
-
-    );
+    let mut last_frame = Instant::now();
+    let mut capture = Capture::new(PathBuf::from("screenshots"), PathBuf::from("recording"));
+    let mut mode = SimulationMode::Kepler;
+    let mut integrator = Integrator::Leapfrog;
+    let mut frame_count: u64 = 0;
+
+    let stars = generate_skybox_stars(scene.skybox.star_count, framebuffer_width, framebuffer_height);
     
+    // Bucle principal
+    while backend.is_open() {
+        if backend.is_key_down(Key::Escape) {
+            break;
+        }
 
-    let nave_uniforms = Uniforms {
-        model_matrix: nave_model_matrix,
-        view_matrix: look_at(&camera.eye, &camera.center, &camera.up),
-        projection_matrix: perspective(
-            45.0 * std::f32::consts::PI / 180.0,
-            framebuffer.width as f32 / framebuffer.height as f32,
-            0.1,
-            1000.0,
-        ),
-        viewport_matrix: Mat4::identity(),
-        time: time as u32,
-        noise: create_noise(),
-    };
+        if backend.is_key_pressed(Key::C) {
+            chase = !chase;
+            println!("Cámara: {}", if chase { "persecución" } else { "libre" });
+        }
+        // Con la cámara de persecución las teclas pilotean la nave; si no,
+        // mueven la cámara y la nave sigue por inercia
+        let controls = if chase {
+            ship_controls(backend.as_ref())
+        } else {
+            handle_input(backend.as_ref(), &mut camera);
+            ShipControls::default()
+        };
+        handle_simulation_input(backend.as_ref(), &mut mode, &mut integrator, &planets);
+        if handle_clock_input(backend.as_ref(), &mut clock, start_time) {
+            // Al saltar de fecha, el modo N-cuerpos vuelve a partir de las
+            // órbitas keplerianas en la fecha nueva
+            if let SimulationMode::NBody(system) = &mut mode {
+                update_positions(&mut planets, clock.time);
+                *system = NBodySystem::from_planets(&planets, system.integrator);
+            }
+        }
+        handle_scale_input(backend.as_ref(), &mut display);
+        // Los shaders se animan con los días transcurridos desde el arranque:
+        // en f32, segundos desde J2000 perderían precisión
+        let time = clock.time;
+        let shader_time = ((time - start_time) / DAY_SECONDS) as f32;
 
-    render_celestial_body(framebuffer, vertex_array, &nave_uniforms, moon_shader);
-}
+        framebuffer.clear();
 
+        // Actualizar posiciones según el modo de simulación
+        match &mut mode {
+            SimulationMode::Kepler => {
+                update_positions(&mut planets, time);
+            }
+            SimulationMode::NBody(system) => {
+                system.write_back(&mut planets);
+                update_kinematic_positions(&mut planets, time);
+            }
+        }
+        update_rotations(&mut planets, time);
+        display.apply(&mut planets);
 
+        // El far de la cámara llega hasta el borde de la escena dibujada, que
+        // cambia con la escala de pantalla
+        let (near, far) = clip_planes(camera.eye, display.extent(&planets));
+        let view_matrix = create_view_matrix(camera.eye, camera.center, camera.up);
+        let projection_matrix = create_perspective_matrix(window_width as f32, window_height as f32, near, far);
+        let viewport_matrix = create_viewport_matrix(framebuffer_width as f32, framebuffer_height as f32);
 
-pub fn render_skybox(framebuffer: &mut Framebuffer, stars: &Vec<(usize, usize)>, color: u32) {
-    framebuffer.set_current_color(color); // Establecer el color para las estrellas
+        let noise = create_noise();
+
+        // Uniforms comunes
+        let mut uniforms = Uniforms {
+            model_matrix: Mat4::identity(),
+            view_matrix,
+            projection_matrix,
+            viewport_matrix,
+            time: shader_time,
+            noise,
+            shader_params: ShaderParams::default(),
+            texture: None,
+            lights: Vec::new(),
+            shadow_maps: Vec::new(),
+            camera_position: camera.eye,
+            atmosphere: None,
+        };
 
-    for &(x, y) in stars {
-        // Centro de la estrella
-        framebuffer.point(x, y, 0.0);
+        render_skybox(&mut framebuffer, &stars, scene.skybox.color); // Dibujar las estrellas
+
+        // Fecha y velocidad del reloj en el título; en N-cuerpos, también la
+        // deriva de energía y momento
+        if frame_count.is_multiple_of(30) {
+            let mut title = format!("Sistema Solar Simulado - {} - {}", scene.name, clock.describe());
+            if let SimulationMode::NBody(system) = &mode {
+                let diagnostics = system.diagnostics();
+                title.push_str(&format!(
+                    " - N-cuerpos ({}) | E = {:.6e} (deriva {:.3e}) | |p| = {:.3e} (deriva {:.3e})",
+                    system.integrator.name(),
+                    diagnostics.energy,
+                    diagnostics.energy_drift,
+                    diagnostics.momentum.norm(),
+                    diagnostics.momentum_drift,
+                ));
+            }
+            backend.set_title(&title);
+        }
 
-        // Resplandor básico más pequeño
-        framebuffer.point(x + 1, y, 0.0); // Derecha
-        framebuffer.point(x - 1, y, 0.0); // Izquierda
-        framebuffer.point(x, y + 1, 0.0); // Abajo
-        framebuffer.point(x, y - 1, 0.0); // Arriba
-    }
-}
+        // Las estrellas iluminan desde su posición actual
+        uniforms.lights = planets.iter().filter_map(Planet::light).collect();
+
+        // Mapa de sombras de cada estrella; las estrellas y los cuerpos
+        // transparentes no proyectan sombra
+        let mesh_for = |planet: &Planet| if planet.texture.is_some() { &textured_vertex_arrays } else { &vertex_arrays };
+        let casters: Vec<ShadowCaster> = planets
+            .iter()
+            .filter(|planet| planet.light().is_none() && !planet.blend.is_transparent())
+            .map(|planet| ShadowCaster { model_matrix: planet.model_matrix(), vertices: mesh_for(planet) })
+            .collect();
+        uniforms.shadow_maps = uniforms
+            .lights
+            .iter()
+            .map(|light| ShadowMap::render(light.position, SHADOW_MAP_SIZE, &casters))
+            .collect();
+
+        // Renderizar planetas: primero lo opaco, después lo transparente de
+        // atrás hacia adelante
+        for planet in planets.iter().filter(|planet| !planet.blend.is_transparent()) {
+            planet.render(&mut framebuffer, mesh_for(planet), &mut uniforms);
+        }
+        ship.render(&mut framebuffer, &nave_vertex_arrays, &mut uniforms);
+
+        // Órbitas alrededor del padre de cada cuerpo; los cuerpos de adelante las tapan
+        for (index, planet) in planets.iter().enumerate() {
+            let place = |offset: Vec3| display.place(&planets, index, planet.offset + offset);
+            match &planet.ephemeris {
+                Some(ephemeris) => render_trajectory(&mut framebuffer, &uniforms, ephemeris, &place, time, &scene.orbits),
+                None => render_orbit(&mut framebuffer, &uniforms, &planet.orbit, &place, time, &scene.orbits),
+            }
+        }
 
+        // Atmósferas, sumadas sobre lo opaco (el cascarón usa la esfera UV)
+        for planet in &planets {
+            planet.render_atmosphere(&mut framebuffer, &textured_vertex_arrays, &mut uniforms);
+        }
 
+        // Cuerpos transparentes y anillos
+        let mut transparent: Vec<&Planet> = planets
+            .iter()
+            .filter(|planet| planet.blend.is_transparent() || planet.ring.is_some())
+            .collect();
+        let camera_distance = |planet: &Planet| (planet.display_position - camera.eye).magnitude();
+        transparent.sort_by(|a, b| camera_distance(b).total_cmp(&camera_distance(a)));
+        for planet in transparent {
+            if planet.blend.is_transparent() {
+                planet.render(&mut framebuffer, mesh_for(planet), &mut uniforms);
+            }
+            planet.render_ring(&mut framebuffer, &mut uniforms);
+        }
+        apply_bloom(&mut framebuffer, &scene.bloom);
 
-fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex]) {
-    // Vertex Shader
-    let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
-    for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, uniforms);
-        transformed_vertices.push(transformed);
-    }
+        handle_display_input(backend.as_ref(), &mut framebuffer);
+        framebuffer.resolve();
 
-    // Primitive Assembly
-    let mut triangles = Vec::new();
-    for i in (0..transformed_vertices.len()).step_by(3) {
-        if i + 2 < transformed_vertices.len() {
-            triangles.push([
-                transformed_vertices[i].clone(),
-                transformed_vertices[i + 1].clone(),
-                transformed_vertices[i + 2].clone(),
-            ]);
+        handle_capture_input(backend.as_ref(), &mut capture, &framebuffer);
+        if let Err(err) = capture.record_frame(&framebuffer) {
+            eprintln!("no se pudo grabar el cuadro: {}", err);
         }
-    }
 
-    // Rasterization
-    let mut fragments = Vec::new();
-    for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2]));
-    }
+        if let Err(err) = backend.present(&framebuffer) {
+            eprintln!("no se pudo presentar el cuadro {}: {}", frame_count, err);
+            std::process::exit(1);
+        }
 
-    // Fragment Processing
-    for fragment in fragments {
-        let x = fragment.position.x as usize;
-        let y = fragment.position.y as usize;
+        // El reloj avanza con el tiempo real transcurrido (o un paso fijo)
+        let now = Instant::now();
+        let real_dt = if options.headless || capture.is_recording() {
+            FIXED_FRAME_TIME
+        } else {
+            now.duration_since(last_frame).as_secs_f64().min(MAX_FRAME_TIME)
+        };
+        last_frame = now;
+        display.update(real_dt as f32);
+        ship.update(&controls, real_dt as f32);
+        if chase {
+            ship.chase(&mut camera, real_dt as f32);
+        }
 
-        if x < framebuffer.width && y < framebuffer.height {
-            let shaded_color = fragment_shader(&fragment, &uniforms);
-            let color = shaded_color.to_hex();
-            framebuffer.set_current_color(color);
-            framebuffer.point(x, y, fragment.depth);
+        let delta = clock.advance(real_dt);
+        if let SimulationMode::NBody(system) = &mut mode {
+            step_nbody(system, &mut planets, clock.time - delta, delta);
         }
+        frame_count += 1;
     }
 }
 
-// Función general para renderizar un cuerpo celeste con su shader específico
-fn render_celestial_body(
-    framebuffer: &mut Framebuffer,
-    vertex_array: &[Vertex],
-    uniforms: &Uniforms,
-    fragment_shader: fn(&Fragment, &Uniforms) -> Color,
-) {
-    // Vertex Shader
-    let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
-    for vertex in vertex_array {
-        let transformed = shaders::vertex_shader(vertex, uniforms); // Usamos el vertex_shader general
-        transformed_vertices.push(transformed);
-    }
 
-    // Rasterización y procesado de fragmentos
-    let mut fragments = Vec::new();
-    for i in (0..transformed_vertices.len()).step_by(3) {
-        if i + 2 < transformed_vertices.len() {
-            let tri = [
-                transformed_vertices[i].clone(),
-                transformed_vertices[i + 1].clone(),
-                transformed_vertices[i + 2].clone(),
-            ];
-            fragments.extend(triangle::triangle(&tri[0], &tri[1], &tri[2]));
-        }
-    }
 
-    // Fragment Shader específico para cada planeta
-    for fragment in fragments {
-        let x = fragment.position.x as usize;
-        let y = fragment.position.y as usize;
 
-        if x < framebuffer.width && y < framebuffer.height {
-            let color = fragment_shader(&fragment, &uniforms).to_hex();
-            framebuffer.set_current_color(color);
-            framebuffer.point(x, y, fragment.depth);
-        }
-    }
+struct Options {
+    scene_path: String,
+    headless: bool,
+    frames: usize,
+    out_dir: PathBuf,
+    format: String,
+    date: Option<String>,
 }
 
-fn apply_emissive_postprocess(framebuffer: &mut Framebuffer) {
-    let width = framebuffer.width;
-    let height = framebuffer.height;
-
-    for y in 0..height {
-        for x in 0..width {
-            let index = y * width + x;
-            let emissive_color = framebuffer.emissive_buffer[index];
-
-            if emissive_color != 0 {
-                // Expande el brillo a los píxeles vecinos
-                for dx in -1..=1 {
-                    for dy in -1..=1 {
-                        let nx = x as isize + dx;
-                        let ny = y as isize + dy;
-
-                        if nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height {
-                            let neighbor_index = ny as usize * width + nx as usize;
-                            framebuffer.buffer[neighbor_index] = framebuffer.buffer[neighbor_index].saturating_add(emissive_color);
-                        }
-                    }
+// Uso: solar_simulation [--scene archivo.toml] [--date AAAA-MM-DD[THH:MM]]
+//                       [--headless --frames N --out dir/ --format png|ppm]
+fn parse_args() -> Options {
+    let mut args = std::env::args().skip(1);
+    let mut options = Options {
+        scene_path: DEFAULT_SCENE.to_string(),
+        headless: false,
+        frames: 1,
+        out_dir: PathBuf::from("frames"),
+        format: "png".to_string(),
+        date: None,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scene" => options.scene_path = expect_value(&mut args, "--scene"),
+            "--headless" => options.headless = true,
+            "--frames" => {
+                let value = expect_value(&mut args, "--frames");
+                options.frames = value.parse().unwrap_or_else(|_| {
+                    eprintln!("--frames espera un número entero, se recibió '{}'", value);
+                    std::process::exit(1);
+                });
+            }
+            "--date" => options.date = Some(expect_value(&mut args, "--date")),
+            "--out" => options.out_dir = PathBuf::from(expect_value(&mut args, "--out")),
+            "--format" => {
+                options.format = expect_value(&mut args, "--format");
+                if options.format != "png" && options.format != "ppm" {
+                    eprintln!("--format debe ser png o ppm, se recibió '{}'", options.format);
+                    std::process::exit(1);
                 }
             }
+            other => {
+                eprintln!("argumento desconocido: {}", other);
+                std::process::exit(1);
+            }
         }
     }
-}
-
-fn render_orbiting_moon(
-    framebuffer: &mut Framebuffer,
-    uniforms: &mut Uniforms,
-    time: f32,
-    orbit_radius: f32,
-    planet_position: Vec3,
-) {
-    // Calcula la posición de la luna en su órbita
-    let angle = time * 0.5; // Ajusta este valor para cambiar la velocidad de la órbita
-    let moon_x = planet_position.x + orbit_radius * angle.cos();
-    let moon_y = planet_position.y + orbit_radius * angle.sin();
-    let moon_position = Vec3::new(moon_x, moon_y, planet_position.z);
-
-    // Configura la matriz de modelo para la luna
-    uniforms.model_matrix = create_model_matrix(moon_position, 0.1, Vec3::new(0.0, 0.0, 0.0));
 
-    // Renderiza la luna usando el shader de luna
-    //render_celestial_body(framebuffer, &get_moon_vertex_array(), uniforms, moon_shader);
+    options
 }
 
+fn expect_value(args: &mut impl Iterator<Item = String>, flag: &str) -> String {
+    args.next().unwrap_or_else(|| {
+        eprintln!("{} requiere un valor", flag);
+        std::process::exit(1);
+    })
+}
 
-fn main() {
-    let window_width = 800;
-    let window_height = 600;
-    let framebuffer_width = 800;
-    let framebuffer_height = 600;
-    let frame_delay = Duration::from_millis(16);
-
-    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
-    let mut window = Window::new(
-        "Sistema Solar Simulado",
-        window_width,
-        window_height,
-        WindowOptions::default(),
-    ).unwrap();
-
-    window.set_position(500, 500);
-    window.update();
-
-    framebuffer.set_background_color(0x060611);
-
-    // Inicializar cámara
-    let mut camera = Camera::new(
-        Vec3::new(0.0, 0.0, 10.0), // Posición inicial de la cámara
-        Vec3::new(0.0, 0.0, 0.0), // Centro de atención
-        Vec3::new(0.0, 1.0, 0.0), // Dirección 'up'
-    );
-
-    // Crear planetas
-    let mut planets = vec![
-        Planet {
-            name: "Sol".to_string(),
-            radius: 1.0,
-            orbit_radius: 0.0, // El Sol no orbita
-            orbit_speed: 0.0,
-            rotation_speed: 0.01,
-            position: Vec3::new(0.0, 0.0, 0.0),
-            shader: star_shader,
-        },
-        Planet {
-            name: "Mercurio".to_string(),
-            radius: 0.2,
-            orbit_radius: 2.0,
-            orbit_speed: 1.5,
-            rotation_speed: 0.1,
-            position: Vec3::new(0.0, 0.0, 0.0),
-            shader: rocky_planet_shader,
-        },
-        Planet {
-            name: "Venus".to_string(),
-            radius: 0.3,
-            orbit_radius: 3.0,
-            orbit_speed: 1.2,
-            rotation_speed: 0.08,
-            position: Vec3::new(0.0, 0.0, 0.0),
-            shader: rocky_planet_shader,
-        },
-        Planet {
-            name: "Tierra".to_string(),
-            radius: 0.4,
-            orbit_radius: 4.0,
-            orbit_speed: 1.0,
-            rotation_speed: 0.1,
-            position: Vec3::new(0.0, 0.0, 0.0),
-            shader: earth_shader,
-        },
-        Planet {
-            name: "Marte".to_string(),
-            radius: 0.3,
-            orbit_radius: 5.0,
-            orbit_speed: 0.8,
-            rotation_speed: 0.09,
-            position: Vec3::new(0.0, 0.0, 0.0),
-            shader: rocky_planet_shader,
-        },
-        // Agregar más planetas si es necesario
-    ];
+// F12 guarda una captura; F9 empieza o termina una grabación
+fn handle_capture_input(backend: &dyn Backend, capture: &mut Capture, framebuffer: &Framebuffer) {
+    if backend.is_key_pressed(Key::F12) {
+        match capture.screenshot(framebuffer) {
+            Ok(path) => println!("Captura guardada en {}", path.display()),
+            Err(err) => eprintln!("no se pudo guardar la captura: {}", err),
+        }
+    }
 
-    // Vertex array de una esfera (modelo básico para todos los planetas)
-    let obj = Obj::load("assets/sphere-1.obj").expect("Failed to load obj");
-    let vertex_arrays = obj.get_vertex_array();
+    if backend.is_key_pressed(Key::F9) {
+        let was_recording = capture.is_recording();
+        match capture.toggle_recording() {
+            Ok(dir) if was_recording => println!("Grabación terminada en {}", dir.display()),
+            Ok(dir) => println!("Grabando en {}", dir.display()),
+            Err(err) => eprintln!("no se pudo iniciar la grabación: {}", err),
+        }
+    }
+}
 
-    let nave_obj = Obj::load("assets/nave.obj").expect("Failed to load nave.obj");
-    let nave_vertex_arrays = nave_obj.get_vertex_array();
+// T cambia el operador de tone mapping; [ y ] bajan y suben la exposición
+fn handle_display_input(backend: &dyn Backend, framebuffer: &mut Framebuffer) {
+    let tone_mapping = &mut framebuffer.tone_mapping;
+    let mut changed = false;
 
+    if backend.is_key_pressed(Key::T) {
+        tone_mapping.operator = tone_mapping.operator.next();
+        changed = true;
+    }
+    if backend.is_key_pressed(Key::LeftBracket) {
+        tone_mapping.exposure /= 1.25;
+        changed = true;
+    }
+    if backend.is_key_pressed(Key::RightBracket) {
+        tone_mapping.exposure *= 1.25;
+        changed = true;
+    }
 
+    if changed {
+        println!("Tone mapping: {} (exposición {:.2})", tone_mapping.operator.name(), tone_mapping.exposure);
+    }
+}
 
-    let mut time = 0.0;
-    // Configuración de las órbitas
-    let planet_orbits = vec![50.0, 100.0, 150.0, 200.0, 250.0]; // Radios de las órbitas
-    let orbit_color = 0x353535; // Color blanco para las órbitas
+// N alterna entre órbitas keplerianas y N-cuerpos; B cambia de integrador
+fn handle_simulation_input(
+    backend: &dyn Backend,
+    mode: &mut SimulationMode,
+    integrator: &mut Integrator,
+    planets: &[Planet],
+) {
+    if backend.is_key_pressed(Key::N) {
+        *mode = match mode {
+            SimulationMode::Kepler => SimulationMode::NBody(NBodySystem::from_planets(planets, *integrator)),
+            SimulationMode::NBody(_) => SimulationMode::Kepler,
+        };
+    }
 
-    // Posiciones iniciales y velocidades angulares para los planetas
-    let mut planet_angles: Vec<f32> = vec![0.0; planet_orbits.len()]; // Ángulos iniciales
-    let angular_speeds: Vec<f32> = vec![0.01, 0.008, 0.006, 0.004, 0.002]; // Velocidades angulares
-    let stars = generate_skybox_stars(100, framebuffer_width, framebuffer_height);
-    let stars = generate_skybox_stars(100, framebuffer_width, framebuffer_height);
-    
-    // Bucle principal
-    while window.is_open() {
-        if window.is_key_down(Key::Escape) {
-            break;
+    if backend.is_key_pressed(Key::B) {
+        *integrator = integrator.next();
+        if let SimulationMode::NBody(system) = mode {
+            system.integrator = *integrator;
         }
+    }
+}
 
-        handle_input(&window, &mut camera);
-
-        framebuffer.clear();
+// M pasa a la siguiente escala de pantalla (real, radios exagerados, comprimida)
+fn handle_scale_input(backend: &dyn Backend, display: &mut DisplayScale) {
+    if backend.is_key_pressed(Key::M) {
+        display.set_mode(display.mode.next());
+        println!("Escala: {}", display.mode.name());
+    }
+}
 
-        let view_matrix = look_at(&camera.eye, &camera.center, &camera.up);
-        let projection_matrix = perspective(
-            45.0 * PI / 180.0,
-            window_width as f32 / window_height as f32,
-            0.1,
-            1000.0,
-        );
-        let viewport_matrix = Mat4::new(
-            framebuffer_width as f32 / 2.0, 0.0, 0.0, framebuffer_width as f32 / 2.0,
-            0.0, framebuffer_height as f32 / 2.0, 0.0, framebuffer_height as f32 / 2.0,
-            0.0, 0.0, 1.0, 0.0,
-            0.0, 0.0, 0.0, 1.0,
-        );
+// Espacio pausa; + y - cambian la velocidad; Retroceso invierte el sentido;
+// punto avanza un cuadro (y pausa); Inicio vuelve a la fecha de partida.
+// Devuelve true si el tiempo saltó.
+fn handle_clock_input(backend: &dyn Backend, clock: &mut SimClock, start_time: f64) -> bool {
+    let mut changed = true;
+    let mut jumped = false;
+
+    if backend.is_key_pressed(Key::Space) {
+        clock.toggle_pause();
+    } else if backend.is_key_pressed(Key::Equal) || backend.is_key_pressed(Key::NumPadPlus) {
+        clock.faster();
+    } else if backend.is_key_pressed(Key::Minus) || backend.is_key_pressed(Key::NumPadMinus) {
+        clock.slower();
+    } else if backend.is_key_pressed(Key::Backspace) {
+        clock.toggle_reverse();
+    } else if backend.is_key_pressed(Key::Period) {
+        clock.step();
+    } else if backend.is_key_pressed(Key::Home) {
+        clock.time = start_time;
+        jumped = true;
+    } else {
+        changed = false;
+    }
 
-        let noise = create_noise();
+    if changed {
+        println!("Reloj: {}", clock.describe());
+    }
+    jumped
+}
 
-        // Uniforms comunes
-        let mut uniforms = Uniforms {
-            model_matrix: Mat4::identity(),
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time: time as u32,
-            noise,
-        };
+// Avanza el sistema N-cuerpos lo mismo que avanzó el reloj, en subpasos.
+// Antes de cada uno, los cuerpos cinemáticos se llevan a su lugar en ese
+// instante (pueden colgar de cuerpos integrados).
+fn step_nbody(system: &mut NBodySystem, planets: &mut [Planet], start: f64, delta: f64) {
+    if delta == 0.0 {
+        return;
+    }
 
-        // Dibuja las órbitas de los planetas
-        for &orbit_radius in &planet_orbits {
-            render_orbit(
-                &mut framebuffer,
-                orbit_radius,               // Radio de la órbita
-                Vec3::new(0.0, 0.0, 0.0),   // Centro del sistema solar
-                orbit_color,                // Color de la órbita
-            );
-        }
-       
-        render_skybox(&mut framebuffer, &stars, 0xFFD966); // Dibujar las estrellas
-        
-
-        // Actualizar y renderizar planetas
-        for planet in &mut planets {
-            planet.update_position(time);
-            planet.render(&mut framebuffer, &vertex_arrays, &mut uniforms, time);
-        }
-        render_nave(&mut framebuffer, &camera, &nave_vertex_arrays, time as i32);
-        
-        
-        window
-            .update_with_buffer(&framebuffer.buffer, framebuffer_width, framebuffer_height)
-            .unwrap();
-
-        time += 0.016; // Incrementa el tiempo para animaciones
-        std::thread::sleep(frame_delay);
+    let substeps = ((delta.abs() / NBODY_MAX_STEP).ceil() as usize).clamp(1, NBODY_MAX_SUBSTEPS);
+    let dt = delta / substeps as f64;
+    for substep in 0..substeps {
+        system.write_back(planets);
+        update_kinematic_positions(planets, start + dt * substep as f64);
+        system.follow(planets);
+        system.step(dt);
     }
 }
 
+// Con la cámara de persecución: flechas arriba y abajo, motor hacia adelante y
+// atrás; izquierda y derecha, R y F, propulsores laterales; W/S cabeceo, A/D
+// guiñada y Q/E alabeo; X estabiliza la rotación y Z frena
+fn ship_controls(backend: &dyn Backend) -> ShipControls {
+    let axis = |positive: Key, negative: Key| {
+        (backend.is_key_down(positive) as i32 - backend.is_key_down(negative) as i32) as f32
+    };
 
+    ShipControls {
+        thrust: Vec3::new(axis(Key::Right, Key::Left), axis(Key::R, Key::F), axis(Key::Up, Key::Down)),
+        torque: Vec3::new(axis(Key::S, Key::W), axis(Key::A, Key::D), axis(Key::Q, Key::E)),
+        stabilize: backend.is_key_down(Key::X),
+        brake: backend.is_key_down(Key::Z),
+    }
+}
 
-
-fn handle_input(window: &Window, camera: &mut Camera) {
+fn handle_input(backend: &dyn Backend, camera: &mut Camera) {
     let movement_speed = 1.0;
     let rotation_speed = PI/50.0;
     let zoom_speed = 0.1;
    
     //  camera orbit controls
-    if window.is_key_down(Key::Left) {
+    if backend.is_key_down(Key::Left) {
       camera.orbit(rotation_speed, 0.0);
     }
-    if window.is_key_down(Key::Right) {
+    if backend.is_key_down(Key::Right) {
       camera.orbit(-rotation_speed, 0.0);
     }
-    if window.is_key_down(Key::W) {
+    if backend.is_key_down(Key::W) {
       camera.orbit(0.0, -rotation_speed);
     }
-    if window.is_key_down(Key::S) {
+    if backend.is_key_down(Key::S) {
       camera.orbit(0.0, rotation_speed);
     }
 
     // Camera movement controls
     let mut movement = Vec3::new(0.0, 0.0, 0.0);
-    if window.is_key_down(Key::A) {
+    if backend.is_key_down(Key::A) {
       movement.x -= movement_speed;
     }
-    if window.is_key_down(Key::D) {
+    if backend.is_key_down(Key::D) {
       movement.x += movement_speed;
     }
-    if window.is_key_down(Key::Q) {
+    if backend.is_key_down(Key::Q) {
       movement.y += movement_speed;
     }
-    if window.is_key_down(Key::E) {
+    if backend.is_key_down(Key::E) {
       movement.y -= movement_speed;
     }
     if movement.magnitude() > 0.0 {
@@ -506,10 +533,10 @@ fn handle_input(window: &Window, camera: &mut Camera) {
     }
 
     // Camera zoom controls
-    if window.is_key_down(Key::Up) {
+    if backend.is_key_down(Key::Up) {
       camera.zoom(zoom_speed);
     }
-    if window.is_key_down(Key::Down) {
+    if backend.is_key_down(Key::Down) {
       camera.zoom(-zoom_speed);
     }
 }
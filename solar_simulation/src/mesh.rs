@@ -0,0 +1,66 @@
+use std::f32::consts::PI;
+
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::vertex::Vertex;
+
+// Esfera UV de radio 0.5 (igual que assets/sphere-1.obj) con coordenadas de
+// textura equirectangulares: u recorre la longitud y v va del polo norte (+y)
+// al polo sur. La costura se duplica para que u no salte de 1 a 0.
+pub fn uv_sphere(stacks: usize, slices: usize) -> Vec<Vertex> {
+    let radius = 0.5;
+    let point = |stack: usize, slice: usize| {
+        let u = slice as f32 / slices as f32;
+        let v = stack as f32 / stacks as f32;
+        let latitude = PI * (0.5 - v);
+        let longitude = 2.0 * PI * u;
+
+        let normal = Vec3::new(
+            latitude.cos() * longitude.cos(),
+            latitude.sin(),
+            -latitude.cos() * longitude.sin(),
+        );
+        Vertex::new(normal * radius, normal, Vec2::new(u, v))
+    };
+
+    let mut vertices = Vec::with_capacity(stacks * slices * 6);
+    for stack in 0..stacks {
+        for slice in 0..slices {
+            let top_left = point(stack, slice);
+            let top_right = point(stack, slice + 1);
+            let bottom_left = point(stack + 1, slice);
+            let bottom_right = point(stack + 1, slice + 1);
+
+            vertices.extend([top_left, bottom_left.clone(), top_right.clone()]);
+            vertices.extend([top_right, bottom_left, bottom_right]);
+        }
+    }
+
+    vertices
+}
+
+// Anillo plano en el plano XZ (normal +y) entre `inner` y `outer`. La
+// coordenada u va de 0 en el borde interior a 1 en el exterior, para muestrear
+// un perfil radial; v recorre el ángulo.
+pub fn annulus(inner: f32, outer: f32, segments: usize) -> Vec<Vertex> {
+    let normal = Vec3::new(0.0, 1.0, 0.0);
+    let point = |radius: f32, u: f32, segment: usize| {
+        let v = segment as f32 / segments as f32;
+        let angle = 2.0 * PI * v;
+        let position = Vec3::new(radius * angle.cos(), 0.0, -radius * angle.sin());
+        Vertex::new(position, normal, Vec2::new(u, v))
+    };
+
+    let mut vertices = Vec::with_capacity(segments * 6);
+    for segment in 0..segments {
+        let inner_start = point(inner, 0.0, segment);
+        let inner_end = point(inner, 0.0, segment + 1);
+        let outer_start = point(outer, 1.0, segment);
+        let outer_end = point(outer, 1.0, segment + 1);
+
+        vertices.extend([inner_start.clone(), outer_start, outer_end.clone()]);
+        vertices.extend([inner_start, outer_end, inner_end]);
+    }
+
+    vertices
+}
@@ -0,0 +1,393 @@
+use nalgebra_glm::{DVec3, Vec3};
+use crate::planet::Planet;
+
+// Constante gravitacional en km³ / (kg s²), las unidades de la simulación
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.6743e-20;
+
+// Suavizado (km) para evitar aceleraciones infinitas en encuentros muy cercanos
+const SOFTENING: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Integrator {
+    Leapfrog,       // drift-kick-drift, simpléctico
+    VelocityVerlet, // kick-drift-kick, simpléctico
+    Rk4,            // Runge-Kutta clásico, no simpléctico (para comparar)
+}
+
+impl Integrator {
+    pub fn next(self) -> Self {
+        match self {
+            Integrator::Leapfrog => Integrator::VelocityVerlet,
+            Integrator::VelocityVerlet => Integrator::Rk4,
+            Integrator::Rk4 => Integrator::Leapfrog,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Integrator::Leapfrog => "Leapfrog",
+            Integrator::VelocityVerlet => "Velocity Verlet",
+            Integrator::Rk4 => "RK4",
+        }
+    }
+}
+
+pub struct Diagnostics {
+    pub energy: f64,
+    pub energy_drift: f64,   // Error relativo respecto a la energía inicial
+    pub momentum: DVec3,
+    pub momentum_drift: f64, // Magnitud del cambio respecto al momento inicial
+}
+
+// Estado de N cuerpos bajo gravedad newtoniana mutua. Se integra en f64 y se
+// copia a los `Planet` después de cada paso. Los cuerpos cinemáticos
+// (efemérides u offset fijo) atraen a los demás pero no se integran: su
+// estado se toma de los `Planet` con `follow`.
+pub struct NBodySystem {
+    pub integrator: Integrator,
+    positions: Vec<DVec3>,
+    velocities: Vec<DVec3>,
+    masses: Vec<f64>,
+    kinematic: Vec<bool>,
+    initial_energy: f64,
+    initial_momentum: DVec3,
+}
+
+impl NBodySystem {
+    // Parte del estado actual de los planetas (posición y velocidad kepleriana)
+    pub fn from_planets(planets: &[Planet], integrator: Integrator) -> Self {
+        let positions = planets.iter().map(|planet| to_dvec3(&planet.position)).collect();
+        let velocities = planets.iter().map(|planet| to_dvec3(&planet.velocity)).collect();
+        let masses = planets.iter().map(|planet| planet.mass as f64).collect();
+        let kinematic = planets.iter().map(Planet::is_kinematic).collect();
+        Self::from_states(positions, velocities, masses, kinematic, integrator)
+    }
+
+    fn from_states(
+        positions: Vec<DVec3>,
+        mut velocities: Vec<DVec3>,
+        masses: Vec<f64>,
+        kinematic: Vec<bool>,
+        integrator: Integrator,
+    ) -> Self {
+        // Pasamos al sistema del centro de masa para que el conjunto no derive.
+        // Si hay cuerpos cinemáticos el marco ya lo fijan ellos.
+        let total_mass: f64 = masses.iter().sum();
+        if total_mass > 0.0 && !kinematic.contains(&true) {
+            let mut center_velocity = DVec3::zeros();
+            for (velocity, mass) in velocities.iter().zip(&masses) {
+                center_velocity += velocity * *mass;
+            }
+            center_velocity /= total_mass;
+            for velocity in velocities.iter_mut() {
+                *velocity -= center_velocity;
+            }
+        }
+
+        let mut system = NBodySystem {
+            integrator,
+            positions,
+            velocities,
+            masses,
+            kinematic,
+            initial_energy: 0.0,
+            initial_momentum: DVec3::zeros(),
+        };
+        system.initial_energy = system.total_energy();
+        system.initial_momentum = system.total_momentum();
+        system
+    }
+
+    pub fn step(&mut self, dt: f64) {
+        match self.integrator {
+            Integrator::Leapfrog => self.step_leapfrog(dt),
+            Integrator::VelocityVerlet => self.step_velocity_verlet(dt),
+            Integrator::Rk4 => self.step_rk4(dt),
+        }
+    }
+
+    // Copia el estado integrado a los planetas (los cinemáticos no se tocan)
+    pub fn write_back(&self, planets: &mut [Planet]) {
+        for (index, planet) in planets.iter_mut().enumerate() {
+            if !self.kinematic[index] {
+                planet.position = to_vec3(&self.positions[index]);
+                planet.velocity = to_vec3(&self.velocities[index]);
+            }
+        }
+    }
+
+    // Toma de los planetas el estado de los cuerpos cinemáticos
+    pub fn follow(&mut self, planets: &[Planet]) {
+        for (index, planet) in planets.iter().enumerate() {
+            if self.kinematic[index] {
+                self.positions[index] = to_dvec3(&planet.position);
+                self.velocities[index] = to_dvec3(&planet.velocity);
+            }
+        }
+    }
+
+    pub fn total_energy(&self) -> f64 {
+        let mut kinetic = 0.0;
+        let mut potential = 0.0;
+
+        for i in 0..self.masses.len() {
+            kinetic += 0.5 * self.masses[i] * self.velocities[i].norm_squared();
+
+            for j in (i + 1)..self.masses.len() {
+                let distance = (self.positions[j] - self.positions[i]).norm_squared() + SOFTENING * SOFTENING;
+                potential -= GRAVITATIONAL_CONSTANT * self.masses[i] * self.masses[j] / distance.sqrt();
+            }
+        }
+
+        kinetic + potential
+    }
+
+    pub fn total_momentum(&self) -> DVec3 {
+        let mut momentum = DVec3::zeros();
+        for (velocity, mass) in self.velocities.iter().zip(&self.masses) {
+            momentum += velocity * *mass;
+        }
+        momentum
+    }
+
+    pub fn diagnostics(&self) -> Diagnostics {
+        let energy = self.total_energy();
+        let momentum = self.total_momentum();
+        let energy_drift = if self.initial_energy != 0.0 {
+            ((energy - self.initial_energy) / self.initial_energy).abs()
+        } else {
+            (energy - self.initial_energy).abs()
+        };
+
+        Diagnostics {
+            energy,
+            energy_drift,
+            momentum,
+            momentum_drift: (momentum - self.initial_momentum).norm(),
+        }
+    }
+
+    fn step_leapfrog(&mut self, dt: f64) {
+        self.drift(dt * 0.5);
+        let accelerations = self.accelerations(&self.positions);
+        self.kick(&accelerations, dt);
+        self.drift(dt * 0.5);
+    }
+
+    fn step_velocity_verlet(&mut self, dt: f64) {
+        let accelerations = self.accelerations(&self.positions);
+        self.kick(&accelerations, dt * 0.5);
+        self.drift(dt);
+        let accelerations = self.accelerations(&self.positions);
+        self.kick(&accelerations, dt * 0.5);
+    }
+
+    fn step_rk4(&mut self, dt: f64) {
+        let x0 = self.positions.clone();
+        let v0 = self.velocities.clone();
+
+        let offset = |base: &[DVec3], delta: &[DVec3], h: f64| -> Vec<DVec3> {
+            base.iter().zip(delta).map(|(b, d)| b + d * h).collect()
+        };
+
+        let k1_x = v0.clone();
+        let k1_v = self.accelerations(&x0);
+
+        let k2_x = offset(&v0, &k1_v, dt * 0.5);
+        let k2_v = self.accelerations(&offset(&x0, &k1_x, dt * 0.5));
+
+        let k3_x = offset(&v0, &k2_v, dt * 0.5);
+        let k3_v = self.accelerations(&offset(&x0, &k2_x, dt * 0.5));
+
+        let k4_x = offset(&v0, &k3_v, dt);
+        let k4_v = self.accelerations(&offset(&x0, &k3_x, dt));
+
+        for i in (0..self.masses.len()).filter(|&i| !self.kinematic[i]) {
+            self.positions[i] = x0[i] + (k1_x[i] + k2_x[i] * 2.0 + k3_x[i] * 2.0 + k4_x[i]) * (dt / 6.0);
+            self.velocities[i] = v0[i] + (k1_v[i] + k2_v[i] * 2.0 + k3_v[i] * 2.0 + k4_v[i]) * (dt / 6.0);
+        }
+    }
+
+    fn drift(&mut self, dt: f64) {
+        for i in (0..self.masses.len()).filter(|&i| !self.kinematic[i]) {
+            self.positions[i] += self.velocities[i] * dt;
+        }
+    }
+
+    fn kick(&mut self, accelerations: &[DVec3], dt: f64) {
+        for i in (0..self.masses.len()).filter(|&i| !self.kinematic[i]) {
+            self.velocities[i] += accelerations[i] * dt;
+        }
+    }
+
+    fn accelerations(&self, positions: &[DVec3]) -> Vec<DVec3> {
+        let mut accelerations = vec![DVec3::zeros(); positions.len()];
+
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                let delta = positions[j] - positions[i];
+                let distance_squared = delta.norm_squared() + SOFTENING * SOFTENING;
+                let inv_distance_cubed = 1.0 / (distance_squared * distance_squared.sqrt());
+
+                accelerations[i] += delta * (GRAVITATIONAL_CONSTANT * self.masses[j] * inv_distance_cubed);
+                accelerations[j] -= delta * (GRAVITATIONAL_CONSTANT * self.masses[i] * inv_distance_cubed);
+            }
+        }
+
+        accelerations
+    }
+}
+
+fn to_dvec3(v: &Vec3) -> DVec3 {
+    DVec3::new(v.x as f64, v.y as f64, v.z as f64)
+}
+
+fn to_vec3(v: &DVec3) -> Vec3 {
+    Vec3::new(v.x as f32, v.y as f32, v.z as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planet::update_kinematic_positions;
+    use crate::scene::Scene;
+    use std::path::Path;
+
+    const SUN_MASS: f64 = 1.989e30;
+    const EARTH_MASS: f64 = 5.972e24;
+    const AU: f64 = 1.496e8;
+    const DAY: f64 = 86_400.0;
+
+    // Tierra en órbita circular alrededor del Sol
+    fn sun_and_earth(integrator: Integrator) -> NBodySystem {
+        let speed = (GRAVITATIONAL_CONSTANT * (SUN_MASS + EARTH_MASS) / AU).sqrt();
+        NBodySystem::from_states(
+            vec![DVec3::zeros(), DVec3::new(AU, 0.0, 0.0)],
+            vec![DVec3::zeros(), DVec3::new(0.0, speed, 0.0)],
+            vec![SUN_MASS, EARTH_MASS],
+            vec![false, false],
+            integrator,
+        )
+    }
+
+    // Máxima deriva de energía en `years` años con pasos de `step_days`
+    fn max_energy_drift(integrator: Integrator, step_days: f64, years: usize) -> f64 {
+        let mut system = sun_and_earth(integrator);
+        let steps = (years as f64 * 365.25 / step_days) as usize;
+        (0..steps)
+            .map(|_| {
+                system.step(step_days * DAY);
+                system.diagnostics().energy_drift
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn symplectic_drift_stays_bounded() {
+        for integrator in [Integrator::Leapfrog, Integrator::VelocityVerlet] {
+            let short = max_energy_drift(integrator, 5.0, 10);
+            let long = max_energy_drift(integrator, 5.0, 200);
+            assert!(long < 1e-4, "{}: deriva {long}", integrator.name());
+            // Acotada: no crece con la duración
+            assert!(long < short * 1.5, "{}: {short} en 10 años, {long} en 200", integrator.name());
+        }
+    }
+
+    #[test]
+    fn rk4_drifts_more_than_symplectic_over_long_runs() {
+        let rk4 = max_energy_drift(Integrator::Rk4, 5.0, 200);
+        for integrator in [Integrator::Leapfrog, Integrator::VelocityVerlet] {
+            let drift = max_energy_drift(integrator, 5.0, 200);
+            assert!(drift < rk4, "{}: {drift} contra RK4 {rk4}", integrator.name());
+        }
+    }
+
+    #[test]
+    fn momentum_is_zero_in_center_of_mass_frame() {
+        for integrator in [Integrator::Leapfrog, Integrator::VelocityVerlet, Integrator::Rk4] {
+            let mut system = sun_and_earth(integrator);
+            // Momento típico de la Tierra, para dar escala a la tolerancia
+            let scale = EARTH_MASS * 30.0;
+            assert!(system.total_momentum().norm() < scale * 1e-12);
+
+            for _ in 0..1000 {
+                system.step(DAY);
+            }
+            let diagnostics = system.diagnostics();
+            assert!(diagnostics.momentum.norm() < scale * 1e-12, "{}", integrator.name());
+            assert!(diagnostics.momentum_drift < scale * 1e-12, "{}", integrator.name());
+        }
+    }
+
+    #[test]
+    fn kinematic_bodies_attract_but_are_not_integrated() {
+        let speed = (GRAVITATIONAL_CONSTANT * SUN_MASS / AU).sqrt();
+        let mut system = NBodySystem::from_states(
+            vec![DVec3::zeros(), DVec3::new(AU, 0.0, 0.0)],
+            vec![DVec3::zeros(), DVec3::new(0.0, speed, 0.0)],
+            vec![SUN_MASS, EARTH_MASS],
+            vec![true, false],
+            Integrator::Leapfrog,
+        );
+        for integrator in [Integrator::Leapfrog, Integrator::VelocityVerlet, Integrator::Rk4] {
+            system.integrator = integrator;
+            for _ in 0..100 {
+                system.step(DAY);
+            }
+        }
+
+        // El Sol no se movió (ni la Tierra lo arrastró) y la Tierra sigue en su órbita
+        assert_eq!(system.positions[0], DVec3::zeros());
+        assert_eq!(system.velocities[0], DVec3::zeros());
+        assert!((system.positions[1].norm() / AU - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn offset_bodies_stay_with_their_parent() {
+        let source = r#"
+name = "Estación"
+camera = { eye = [0.0, 0.0, 5.0] }
+
+[[body]]
+name = "Sol"
+radius = 696000.0
+mass = 1.989e30
+shader = { name = "star" }
+
+[[body]]
+name = "Tierra"
+radius = 6371.0
+mass = 5.97e24
+orbit = { semi_major_axis = 1.0 }
+shader = { name = "moon" }
+
+[[body]]
+name = "Estación"
+parent = "Tierra"
+radius = 1.0
+mass = 0.0
+offset = [42164.0, 0.0, 0.0]
+shader = { name = "moon" }
+"#;
+        let mut planets = Scene::parse(source, Path::new("")).unwrap().bodies;
+        crate::planet::update_positions(&mut planets, 0.0);
+        assert!(!planets[1].is_kinematic() && planets[2].is_kinematic());
+
+        // Como en el programa: antes de cada paso, los cinemáticos siguen a su padre
+        let mut system = NBodySystem::from_planets(&planets, Integrator::Leapfrog);
+        for step in 0..100 {
+            system.write_back(&mut planets);
+            update_kinematic_positions(&mut planets, step as f64 * DAY);
+            system.follow(&planets);
+            system.step(DAY);
+        }
+        system.write_back(&mut planets);
+        update_kinematic_positions(&mut planets, 100.0 * DAY);
+
+        assert_eq!(planets[2].position, planets[1].position + Vec3::new(42164.0, 0.0, 0.0));
+        assert_eq!(planets[2].velocity, planets[1].velocity);
+        // La Tierra sí se integró: sigue a 1 UA del Sol
+        let distance = (planets[1].position - planets[0].position).magnitude() as f64;
+        assert!((distance / AU - 1.0).abs() < 1e-2);
+    }
+}
@@ -1,44 +1,284 @@
+use crate::color::Color;
+use crate::ephemeris::Ephemeris;
 use crate::framebuffer::Framebuffer;
+use crate::line;
+use crate::vertex::Vertex;
+use crate::Uniforms;
 use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+use std::f64::consts::TAU;
 
-pub fn render_orbit(framebuffer: &mut Framebuffer, orbit_radius: f32, center: Vec3, color: u32) {
-    let num_segments = 100; // Resolución de la órbita (más segmentos = círculo más suave)
-    let framebuffer_width = framebuffer.width as f32;
-    let framebuffer_height = framebuffer.height as f32;
+// Segmentos con los que se dibuja cada órbita
+pub const ORBIT_SEGMENTS: usize = 128;
+// Tope de segmentos de una trayectoria de efemérides
+const MAX_TRAJECTORY_SEGMENTS: usize = 4096;
+// Opacidad del extremo más alejado del cuerpo cuando la órbita se desvanece
+const FADED_ALPHA: f32 = 0.15;
 
-    // Calculamos el centro de la órbita en coordenadas del framebuffer
-    let center_x = framebuffer_width / 2.0 + center.x;
-    let center_y = framebuffer_height / 2.0 - center.y;
+// Cómo se dibujan las órbitas
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitStyle {
+    pub color: Color,
+    pub fade: bool,     // Desvanecer cada órbita a partir de su cuerpo
+    pub thickness: f32, // Grosor de la línea en píxeles
+}
+
+// Elementos orbitales clásicos (ángulos en radianes, distancias en km y
+// tiempo en segundos). El plano de referencia es XY, igual que el que usaban
+// las órbitas circulares anteriores.
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f32,          // a
+    pub eccentricity: f32,             // e (0 <= e < 1)
+    pub inclination: f32,              // i
+    pub longitude_ascending_node: f32, // Ω
+    pub argument_periapsis: f32,       // ω
+    pub mean_anomaly_epoch: f32,       // M0, anomalía media en t = 0
+    pub mean_motion: f32,              // n, radianes por segundo
+}
+
+impl OrbitalElements {
+    // Cuerpo fijo en el origen (por ejemplo, la estrella central)
+    pub const fn stationary() -> Self {
+        OrbitalElements {
+            semi_major_axis: 0.0,
+            eccentricity: 0.0,
+            inclination: 0.0,
+            longitude_ascending_node: 0.0,
+            argument_periapsis: 0.0,
+            mean_anomaly_epoch: 0.0,
+            mean_motion: 0.0,
+        }
+    }
+
+    // En f64: tras años de simulación, n * t en f32 perdería la posición
+    pub fn mean_anomaly(&self, time: f64) -> f32 {
+        (self.mean_anomaly_epoch as f64 + self.mean_motion as f64 * time).rem_euclid(TAU) as f32
+    }
 
-    // Calculamos el primer punto de la órbita
-    let mut previous_point = (
-        center_x + orbit_radius * theta(0).cos(),
-        center_y + orbit_radius * theta(0).sin(),
-    );
+    pub fn position_at(&self, time: f64) -> Vec3 {
+        self.state_at(self.mean_anomaly(time), self.mean_motion).0
+    }
+
+    pub fn velocity_at(&self, time: f64) -> Vec3 {
+        self.state_at(self.mean_anomaly(time), self.mean_motion).1
+    }
 
-    for i in 1..=num_segments {
-        let angle = 2.0 * std::f32::consts::PI * i as f32 / num_segments as f32;
-        let next_point = (
-            center_x + orbit_radius * angle.cos(),
-            center_y + orbit_radius * angle.sin(),
-        );
+    // Posición y velocidad para una anomalía media dada. El movimiento medio se
+    // recibe aparte porque en modo N-cuerpos se deriva de la masa central.
+    pub fn state_at(&self, mean_anomaly: f32, mean_motion: f32) -> (Vec3, Vec3) {
+        let e = self.eccentricity.clamp(0.0, 0.999);
+        let a = self.semi_major_axis;
+        let b = a * (1.0 - e * e).sqrt();
 
-        // Convertimos los puntos en coordenadas enteras
-        let x1 = previous_point.0 as usize;
-        let y1 = previous_point.1 as usize;
-        let x2 = next_point.0 as usize;
-        let y2 = next_point.1 as usize;
+        let eccentric_anomaly = solve_kepler(mean_anomaly, e);
+        let (sin_e, cos_e) = eccentric_anomaly.sin_cos();
+        let eccentric_rate = mean_motion / (1.0 - e * cos_e);
 
-        // Dibujamos la línea entre el punto actual y el siguiente
-        framebuffer.draw_line(x1, y1, x2, y2, color);
+        // Posición y velocidad en el plano perifocal (x hacia el periapsis)
+        let position = self.perifocal_to_reference(a * (cos_e - e), b * sin_e);
+        let velocity = self.perifocal_to_reference(-a * sin_e * eccentric_rate, b * cos_e * eccentric_rate);
 
-        // Actualizamos el punto anterior
-        previous_point = next_point;
+        (position, velocity)
+    }
+
+    // Rota del plano perifocal al plano de referencia: Rz(Ω) · Rx(i) · Rz(ω)
+    fn perifocal_to_reference(&self, x: f32, y: f32) -> Vec3 {
+        let (sin_w, cos_w) = self.argument_periapsis.sin_cos();
+        let (sin_o, cos_o) = self.longitude_ascending_node.sin_cos();
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+
+        Vec3::new(
+            (cos_o * cos_w - sin_o * sin_w * cos_i) * x + (-cos_o * sin_w - sin_o * cos_w * cos_i) * y,
+            (sin_o * cos_w + cos_o * sin_w * cos_i) * x + (-sin_o * sin_w + cos_o * cos_w * cos_i) * y,
+            (sin_w * sin_i) * x + (cos_w * sin_i) * y,
+        )
     }
 }
 
-// Función auxiliar para calcular el ángulo (theta)
-fn theta(segment: i32) -> f32 {
-    2.0 * std::f32::consts::PI * segment as f32 / 100.0
+// Resuelve la ecuación de Kepler M = E - e·sin(E) con Newton-Raphson
+pub fn solve_kepler(mean_anomaly: f32, eccentricity: f32) -> f32 {
+    // Normalizamos M a [-π, π] para que Newton converja rápido
+    let m = (mean_anomaly + PI).rem_euclid(2.0 * PI) - PI;
+
+    // Para excentricidades altas (cometas) E = π es un mejor punto de partida
+    let mut e_anomaly = if eccentricity < 0.8 { m } else { PI * m.signum() };
+
+    for _ in 0..30 {
+        let f = e_anomaly - eccentricity * e_anomaly.sin() - m;
+        let f_prime = 1.0 - eccentricity * e_anomaly.cos();
+        let delta = f / f_prime;
+        e_anomaly -= delta;
+
+        if delta.abs() < 1e-6 {
+            break;
+        }
+    }
+
+    e_anomaly
 }
 
+// Dibuja la elipse de la órbita en el mundo pasando por la cámara: se recorta
+// contra el frustum y la tapan los cuerpos que estén delante. `place` lleva
+// cada punto (relativo al padre, en km) a la escena dibujada, con la misma
+// escala de pantalla que el cuerpo. Con `fade`, la línea es opaca justo detrás
+// del cuerpo y se desvanece a lo largo de la vuelta, como una estela.
+pub fn render_orbit(
+    framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    orbit: &OrbitalElements,
+    place: &dyn Fn(Vec3) -> Vec3,
+    time: f64,
+    style: &OrbitStyle,
+) {
+    if orbit.semi_major_axis <= 0.0 {
+        return;
+    }
+
+    let body_anomaly = orbit.mean_anomaly(time);
+    let e = orbit.eccentricity.clamp(0.0, 0.999);
+    let color = style.color;
+
+    // Muestreo uniforme en la anomalía excéntrica: los puntos quedan repartidos
+    // a lo largo de la elipse aunque sea muy excéntrica
+    let points: Vec<Vertex> = (0..=ORBIT_SEGMENTS)
+        .map(|i| {
+            let eccentric_anomaly = 2.0 * PI * i as f32 / ORBIT_SEGMENTS as f32;
+            let mean_anomaly = eccentric_anomaly - e * eccentric_anomaly.sin();
+            let position = place(orbit.state_at(mean_anomaly, orbit.mean_motion).0);
+
+            // Fracción de vuelta desde el punto hasta el cuerpo, hacia adelante
+            let behind = (body_anomaly - mean_anomaly).rem_euclid(2.0 * PI) / (2.0 * PI);
+            let alpha = if style.fade { 1.0 - behind * (1.0 - FADED_ALPHA) } else { 1.0 };
+
+            Vertex::new_with_color(position, color.with_alpha(color.a * alpha))
+        })
+        .collect();
+
+    line::polyline(framebuffer, uniforms, &points, style.thickness);
+}
+
+// Trayectoria de un cuerpo con efemérides (ubicada con `place`, como las
+// órbitas) en todo el intervalo de la tabla. Se muestrea más fino que la tabla (la interpolación
+// es suave entre muestras). Con `fade`, lo recorrido es opaco junto al cuerpo
+// y se desvanece hacia el comienzo de la tabla; lo que falta recorrer queda tenue.
+pub fn render_trajectory(
+    framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    ephemeris: &Ephemeris,
+    place: &dyn Fn(Vec3) -> Vec3,
+    time: f64,
+    style: &OrbitStyle,
+) {
+    let (start, end) = (ephemeris.start(), ephemeris.end());
+    if end <= start {
+        return;
+    }
+
+    let segments = (ephemeris.samples.len() * 4).clamp(ORBIT_SEGMENTS, MAX_TRAJECTORY_SEGMENTS);
+    let color = style.color;
+    let points: Vec<Vertex> = (0..=segments)
+        .map(|i| {
+            let sample_time = start + (end - start) * i as f64 / segments as f64;
+            let position = place(ephemeris.state_at(sample_time).0);
+
+            let behind = (time - sample_time) / (end - start);
+            let alpha = match (style.fade, behind >= 0.0) {
+                (false, _) => 1.0,
+                (true, true) => 1.0 - behind as f32 * (1.0 - FADED_ALPHA),
+                (true, false) => FADED_ALPHA,
+            };
+
+            Vertex::new_with_color(position, color.with_alpha(color.a * alpha))
+        })
+        .collect();
+
+    line::polyline(framebuffer, uniforms, &points, style.thickness);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements(eccentricity: f32) -> OrbitalElements {
+        OrbitalElements {
+            semi_major_axis: 1.5e8,
+            eccentricity,
+            inclination: 0.3,
+            longitude_ascending_node: 1.1,
+            argument_periapsis: 2.0,
+            mean_anomaly_epoch: 0.0,
+            mean_motion: 2e-7,
+        }
+    }
+
+    fn relative_error(value: f32, expected: f32) -> f32 {
+        ((value - expected) / expected).abs()
+    }
+
+    #[test]
+    fn circular_orbit_anomalies_match() {
+        for i in 0..16 {
+            let m = -PI + 2.0 * PI * i as f32 / 16.0;
+            assert!((solve_kepler(m, 0.0) - m).abs() < 1e-6, "M = {m}");
+        }
+    }
+
+    #[test]
+    fn solves_kepler_for_eccentric_orbits() {
+        for eccentricity in [0.2, 0.6, 0.9, 0.99] {
+            for i in 0..64 {
+                let m = -PI + 2.0 * PI * (i as f32 + 0.5) / 64.0;
+                let e_anomaly = solve_kepler(m, eccentricity);
+                let residual = e_anomaly - eccentricity * e_anomaly.sin() - m;
+                assert!(residual.abs() < 1e-5, "e = {eccentricity}, M = {m}: residuo {residual}");
+            }
+        }
+    }
+
+    #[test]
+    fn mean_anomaly_wraps_around() {
+        let e_anomaly = solve_kepler(0.5, 0.9);
+        assert!((solve_kepler(0.5 + 2.0 * PI, 0.9) - e_anomaly).abs() < 1e-5);
+        assert!((solve_kepler(0.5 - 4.0 * PI, 0.9) - e_anomaly).abs() < 1e-5);
+    }
+
+    #[test]
+    fn periapsis_and_apoapsis_distances() {
+        for eccentricity in [0.0, 0.3, 0.9] {
+            let orbit = elements(eccentricity);
+            let a = orbit.semi_major_axis;
+            let periapsis = orbit.state_at(0.0, orbit.mean_motion).0.magnitude();
+            let apoapsis = orbit.state_at(PI, orbit.mean_motion).0.magnitude();
+
+            assert!(relative_error(periapsis, a * (1.0 - eccentricity)) < 1e-4, "e = {eccentricity}");
+            assert!(relative_error(apoapsis, a * (1.0 + eccentricity)) < 1e-4, "e = {eccentricity}");
+        }
+    }
+
+    #[test]
+    fn velocity_follows_vis_viva() {
+        let orbit = elements(0.6);
+        let a = orbit.semi_major_axis;
+        // μ = n² a³
+        let mu = (orbit.mean_motion as f64).powi(2) * (a as f64).powi(3);
+
+        for i in 0..8 {
+            let (position, velocity) = orbit.state_at(2.0 * PI * i as f32 / 8.0, orbit.mean_motion);
+            let r = position.magnitude() as f64;
+            let expected = (mu * (2.0 / r - 1.0 / a as f64)).sqrt() as f32;
+            assert!(relative_error(velocity.magnitude(), expected) < 1e-4, "paso {i}");
+        }
+
+        // En los ápsides la velocidad es perpendicular al radio
+        let (position, velocity) = orbit.state_at(0.0, orbit.mean_motion);
+        assert!(position.normalize().dot(&velocity.normalize()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn stationary_body_stays_at_origin() {
+        let orbit = OrbitalElements::stationary();
+        assert_eq!(orbit.position_at(1e9), Vec3::zeros());
+        assert_eq!(orbit.velocity_at(1e9), Vec3::zeros());
+    }
+}
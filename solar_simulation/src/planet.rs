@@ -1,24 +1,148 @@
-use crate::shaders::{vertex_shader, fragment_shader}; // Importa los shaders
-use crate::framebuffer::Framebuffer;
+use crate::framebuffer::{BlendMode, Framebuffer};
+use crate::orbit::OrbitalElements;
+use crate::ephemeris::Ephemeris;
+use crate::shaders::{ShaderFn, ShaderParams};
 use crate::vertex::Vertex;
 use crate::Uniforms;
-use nalgebra_glm::Vec3;
+use crate::texture::Texture;
+use crate::light::Light;
+use crate::atmosphere::{atmosphere_shader, Atmosphere};
+use crate::ring::{ring_shader, Ring};
+use crate::shaders::ShaderParam;
+use nalgebra_glm::{rotation, Mat4, Vec3};
+use std::f64::consts::TAU;
+use std::sync::Arc;
 
+// Nodo del grafo de escena. Cada cuerpo puede colgar de otro (`parent`): su
+// órbita y su `offset` son locales al marco del padre, así las lunas orbitan
+// su planeta y una estación puede quedar fija junto a un cuerpo.
+//
+// El estado físico está en km, kg y segundos. Lo que se dibuja sale de
+// `display_position` y `display_radius`, que la escala de pantalla
+// (display.rs) calcula en cada cuadro a partir de él.
 pub struct Planet {
     pub name: String,
-    pub radius: f32,                // Tamaño del planeta
-    pub orbit_radius: f32,          // Radio de la órbita
-    pub orbit_speed: f32,           // Velocidad orbital
-    pub rotation_speed: f32,        // Velocidad de rotación
-    pub position: Vec3,             // Posición actual
-    pub shader: fn(&crate::Fragment, &Uniforms) -> crate::Color, // Shader del planeta
+    pub parent: Option<usize>,      // Índice del padre en la escena (siempre declarado antes)
+    pub offset: Vec3,               // Posición fija respecto al padre (km), sumada a la órbita
+    pub radius: f32,                // Radio en km
+    pub orbit: OrbitalElements,     // Elementos orbitales keplerianos
+    pub ephemeris: Option<Ephemeris>, // Si tiene, reemplaza a la órbita kepleriana
+    pub pole: Vec3,                 // Polo norte (eje de rotación, unitario) en el mundo
+    pub rotation_speed: f32,        // Rotación sidérea alrededor del polo (rad/s)
+    pub tidally_locked: bool,       // Muestra siempre la misma cara a su padre
+    pub rotation: f32,              // Ángulo de rotación actual (rad), ver update_rotations
+    pub mass: f32,                  // Masa en kg
+    pub position: Vec3,             // Posición actual en el mundo (km)
+    pub velocity: Vec3,             // Velocidad actual en el mundo (km/s)
+    pub display_position: Vec3,     // Posición en la escena dibujada
+    pub display_radius: f32,        // Radio en la escena dibujada
+    pub shader: ShaderFn,           // Shader del planeta
+    pub shader_params: ShaderParams, // Parámetros del shader definidos en la escena
+    pub texture: Option<Arc<Texture>>, // Textura opcional (por ejemplo, un mapa equirectangular)
+    pub luminosity: f32,            // Intensidad de la luz que emite (0 si no emite)
+    pub blend: BlendMode,           // Opaco, o cómo se mezcla si es transparente (nubes, atmósferas)
+    pub atmosphere: Option<Atmosphere>, // Cascarón de dispersión alrededor del cuerpo
+    pub ring: Option<Ring>,         // Anillos (transparentes, en el plano ecuatorial)
+}
+
+// Posiciones y velocidades en el mundo de todos los cuerpos. Los padres van
+// antes que los hijos, así cada nodo compone su transformación local con la
+// del padre ya actualizada. El marco que heredan los hijos es sólo la
+// traslación del padre (una luna no gira con la rotación de su planeta), por
+// eso componer las matrices se reduce a sumar posiciones.
+pub fn update_positions(planets: &mut [Planet], time: f64) {
+    update_where(planets, time, |_| true);
+}
+
+// Sólo los cuerpos cinemáticos (ver `is_kinematic`): en el modo N-cuerpos
+// siguen su tabla o su padre mientras los demás se integran
+pub fn update_kinematic_positions(planets: &mut [Planet], time: f64) {
+    update_where(planets, time, Planet::is_kinematic);
+}
+
+fn update_where(planets: &mut [Planet], time: f64, selected: impl Fn(&Planet) -> bool) {
+    for index in 0..planets.len() {
+        if !selected(&planets[index]) {
+            continue;
+        }
+
+        let (origin, origin_velocity) = match planets[index].parent {
+            Some(parent) => (planets[parent].position, planets[parent].velocity),
+            None => (Vec3::zeros(), Vec3::zeros()),
+        };
+
+        let planet = &mut planets[index];
+        let (position, velocity) = match &planet.ephemeris {
+            Some(ephemeris) => ephemeris.state_at(time),
+            None => (planet.orbit.position_at(time), planet.orbit.velocity_at(time)),
+        };
+        planet.position = origin + planet.offset + position;
+        planet.velocity = origin_velocity + velocity;
+    }
+}
+
+// Ángulo de rotación de cada cuerpo alrededor de su polo. Los que tienen
+// rotación sincrónica apuntan su meridiano cero (el eje x del modelo) hacia
+// el padre, o hacia el origen si no tienen; así la Luna muestra siempre la
+// misma cara a la Tierra. Va después de las posiciones, en los dos modos de
+// simulación.
+pub fn update_rotations(planets: &mut [Planet], time: f64) {
+    for index in 0..planets.len() {
+        let toward = match planets[index].parent {
+            Some(parent) => planets[parent].position - planets[index].position,
+            None => -planets[index].position,
+        };
+
+        let planet = &mut planets[index];
+        planet.rotation = if planet.tidally_locked {
+            let (equator_x, equator_z) = equatorial_axes(&planet.pole);
+            // Ry(θ) lleva el eje x a (cos θ, 0, -sin θ)
+            (-toward.dot(&equator_z)).atan2(toward.dot(&equator_x))
+        } else {
+            (planet.rotation_speed as f64 * time).rem_euclid(TAU) as f32
+        };
+    }
+}
+
+// Ejes del plano ecuatorial: x apunta al nodo ascendente del ecuador sobre el
+// plano XY (la eclíptica) y z completa la base (x, polo, z)
+fn equatorial_axes(pole: &Vec3) -> (Vec3, Vec3) {
+    let node = Vec3::z().cross(pole);
+    let equator_x = if node.magnitude() > 1e-6 { node.normalize() } else { Vec3::x() };
+    (equator_x, equator_x.cross(pole))
 }
 
 impl Planet {
-    pub fn update_position(&mut self, time: f32) {
-        let angle = time * self.orbit_speed;
-        self.position.x = self.orbit_radius * angle.cos();
-        self.position.y = self.orbit_radius * angle.sin();
+    // Los cuerpos con efemérides o fijos a su padre (offset) no se integran
+    // en el modo N-cuerpos: su movimiento está dado
+    pub fn is_kinematic(&self) -> bool {
+        self.ephemeris.is_some() || self.offset != Vec3::zeros()
+    }
+
+    // Lleva el eje y del modelo al polo, sin la rotación diaria (los anillos
+    // usan sólo esto: quedan en el plano ecuatorial)
+    fn pole_matrix(&self) -> Mat4 {
+        let (x, z) = equatorial_axes(&self.pole);
+        let y = self.pole;
+        Mat4::new(
+            x.x, y.x, z.x, 0.0,
+            x.y, y.y, z.y, 0.0,
+            x.z, y.z, z.z, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        )
+    }
+
+    // Luz puntual en la posición actual del cuerpo, si emite luz
+    pub fn light(&self) -> Option<Light> {
+        (self.luminosity > 0.0).then_some(Light { position: self.display_position, intensity: self.luminosity })
+    }
+
+    // La esfera del modelo tiene radio 0.5: la escala es el diámetro. Gira
+    // alrededor de su eje y, que se lleva al polo.
+    pub fn model_matrix(&self) -> Mat4 {
+        crate::create_model_matrix(self.display_position, 2.0 * self.display_radius, Vec3::zeros())
+            * self.pole_matrix()
+            * rotation(self.rotation, &Vec3::y())
     }
 
     pub fn render(
@@ -26,20 +150,211 @@ impl Planet {
         framebuffer: &mut Framebuffer,
         vertex_array: &[Vertex],
         uniforms: &mut Uniforms,
-        time: f32, // Agrega time como argumento
     ) {
-        uniforms.model_matrix = crate::create_model_matrix(
-            self.position,
-            self.radius,
-            Vec3::new(0.0, time * self.rotation_speed, 0.0), // Usa el argumento time
-        );
+        uniforms.model_matrix = self.model_matrix();
+        uniforms.shader_params = self.shader_params.clone();
+        uniforms.texture = self.texture.clone();
     
-        crate::render_celestial_body(
+        crate::render_blended_body(
             framebuffer,
             vertex_array,
             uniforms,
             self.shader,
+            self.blend,
         );
     }
-    
+
+    // Dibuja el cascarón de la atmósfera, si tiene. Se suma a lo que hay
+    // detrás, así que va después de todo lo opaco y el orden no importa.
+    pub fn render_atmosphere(
+        &self,
+        framebuffer: &mut Framebuffer,
+        vertex_array: &[Vertex],
+        uniforms: &mut Uniforms,
+    ) {
+        let Some(atmosphere) = self.atmosphere else {
+            return;
+        };
+
+        uniforms.model_matrix = crate::create_model_matrix(
+            self.display_position,
+            2.0 * self.display_radius * (1.0 + atmosphere.thickness),
+            Vec3::new(0.0, 0.0, 0.0),
+        );
+        uniforms.atmosphere = Some(atmosphere);
+
+        crate::render_blended_body(
+            framebuffer,
+            vertex_array,
+            uniforms,
+            atmosphere_shader,
+            BlendMode::Additive,
+        );
+        uniforms.atmosphere = None;
+    }
+
+    // Dibuja los anillos, si tiene, en el plano ecuatorial (más su propia
+    // inclinación). Son transparentes: van en la pasada de atrás hacia
+    // adelante, después del planeta que les da sombra.
+    pub fn render_ring(&self, framebuffer: &mut Framebuffer, uniforms: &mut Uniforms) {
+        let Some(ring) = &self.ring else {
+            return;
+        };
+
+        uniforms.model_matrix =
+            crate::create_model_matrix(self.display_position, 2.0 * self.display_radius, Vec3::zeros())
+                * self.pole_matrix()
+                * rotation(ring.tilt, &Vec3::x());
+        uniforms.shader_params = ShaderParams::default();
+        uniforms.shader_params.insert("opacity", ShaderParam::Number(ring.opacity));
+        uniforms.texture = Some(ring.profile.clone());
+
+        crate::render_blended_body(framebuffer, &ring.vertices, uniforms, ring_shader, BlendMode::Alpha);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::Scene;
+    use crate::units::DAY_SECONDS;
+    use nalgebra_glm::{Mat3, Vec4};
+    use std::path::Path;
+
+    fn solar_system() -> Scene {
+        Scene::load("assets/scenes/solar_system.toml").unwrap()
+    }
+
+    // Sol, planeta, luna del planeta y una estación fija junto a la luna
+    const HIERARCHY: &str = r#"
+name = "Jerarquía"
+camera = { eye = [0.0, 0.0, 5.0] }
+
+[[body]]
+name = "Sol"
+radius = 696000.0
+mass = 1.989e30
+luminosity = 1.0
+shader = { name = "star" }
+
+[[body]]
+name = "Planeta"
+radius = 6371.0
+mass = 5.97e24
+orbit = { semi_major_axis = 1.0, eccentricity = 0.2, inclination = 10.0, mean_anomaly = 30.0 }
+shader = { name = "moon" }
+
+[[body]]
+name = "Luna"
+parent = "Planeta"
+radius = 1737.0
+mass = 7.35e22
+orbit = { semi_major_axis = 0.00257, eccentricity = 0.05, inclination = 5.0 }
+shader = { name = "moon" }
+
+[[body]]
+name = "Estación"
+parent = "Luna"
+radius = 1.0
+mass = 0.0
+offset = [3000.0, 0.0, 500.0]
+shader = { name = "moon" }
+"#;
+
+    // Dirección en el mundo de un eje del modelo, sin traslación ni escala
+    fn world_axis(planet: &Planet, axis: Vec3) -> Vec3 {
+        let axis = planet.pole_matrix() * rotation(planet.rotation, &Vec3::y()) * Vec4::new(axis.x, axis.y, axis.z, 0.0);
+        Vec3::new(axis.x, axis.y, axis.z)
+    }
+
+    #[test]
+    fn children_follow_their_parents() {
+        let mut scene = Scene::parse(HIERARCHY, Path::new("")).unwrap();
+        let planets = &mut scene.bodies;
+        let station_offset = Vec3::new(3000.0, 0.0, 500.0);
+
+        for day in [0.0, 3.5, 40.0, 200.0, 1000.0] {
+            let time = day * DAY_SECONDS;
+            update_positions(planets, time);
+            let local = |index: usize| (planets[index].orbit.position_at(time), planets[index].orbit.velocity_at(time));
+
+            // El planeta cuelga del origen; la luna del planeta y la estación
+            // (nieta del planeta) de la luna, con su offset fijo
+            let (position, velocity) = local(1);
+            assert_eq!((planets[1].position, planets[1].velocity), (position, velocity), "día {day}");
+
+            let (position, velocity) = local(2);
+            assert_eq!(planets[2].position, planets[1].position + position, "día {day}");
+            assert_eq!(planets[2].velocity, planets[1].velocity + velocity, "día {day}");
+
+            assert_eq!(planets[3].position, planets[2].position + station_offset, "día {day}");
+            assert_eq!(planets[3].velocity, planets[2].velocity, "día {day}");
+        }
+    }
+
+    #[test]
+    fn pole_matrix_maps_y_onto_the_pole() {
+        let mut scene = solar_system();
+        let poles = [Vec3::z(), -Vec3::z(), Vec3::x(), Vec3::new(0.3, -0.4, 0.5).normalize()];
+        let tilted = scene.bodies.iter().map(|planet| planet.pole).collect::<Vec<_>>();
+
+        let planet = &mut scene.bodies[0];
+        for pole in poles.into_iter().chain(tilted) {
+            planet.pole = pole;
+            let matrix = planet.pole_matrix();
+            let y = matrix * Vec4::y();
+            assert!((Vec3::new(y.x, y.y, y.z) - pole).magnitude() < 1e-6, "{pole:?}");
+
+            // Rotación pura: la base (x, polo, z) es ortonormal y derecha
+            let basis = matrix.fixed_view::<3, 3>(0, 0).into_owned();
+            assert!((basis.transpose() * basis - Mat3::identity()).norm() < 1e-5, "{pole:?}");
+            assert!((basis.determinant() - 1.0).abs() < 1e-5, "{pole:?}");
+
+            // La rotación diaria gira alrededor del polo: no lo mueve
+            for angle in [0.5, 2.0, 4.0] {
+                planet.rotation = angle;
+                assert!((world_axis(planet, Vec3::y()) - pole).magnitude() < 1e-6, "{pole:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn scene_poles_lean_by_the_axial_tilt() {
+        let scene = solar_system();
+        let earth = scene.bodies.iter().find(|planet| planet.name == "Tierra").unwrap();
+        let orbit = &earth.orbit;
+        let normal = Vec3::new(
+            orbit.longitude_ascending_node.sin() * orbit.inclination.sin(),
+            -orbit.longitude_ascending_node.cos() * orbit.inclination.sin(),
+            orbit.inclination.cos(),
+        );
+        let tilt = world_axis(earth, Vec3::y()).angle(&normal).to_degrees();
+        assert!((tilt - 23.44).abs() < 1e-3, "{tilt}");
+    }
+
+    #[test]
+    fn tidally_locked_bodies_keep_the_same_face_toward_their_parent() {
+        let mut scene = solar_system();
+        let locked: Vec<usize> = (0..scene.bodies.len()).filter(|&index| scene.bodies[index].tidally_locked).collect();
+        assert!(!locked.is_empty());
+
+        for step in 0..60 {
+            let time = step as f64 * DAY_SECONDS;
+            update_positions(&mut scene.bodies, time);
+            update_rotations(&mut scene.bodies, time);
+
+            for &index in &locked {
+                let planet = &scene.bodies[index];
+                let parent = &scene.bodies[planet.parent.unwrap()];
+                let toward = (parent.position - planet.position).normalize();
+                // El meridiano cero (eje x del modelo) mira al padre; lo que
+                // sobra es la componente de `toward` a lo largo del polo
+                let face = world_axis(planet, Vec3::x());
+                let along_pole = toward.dot(&planet.pole);
+                let expected = (1.0 - along_pole * along_pole).sqrt();
+                assert!((face.dot(&toward) - expected).abs() < 1e-4, "{} el día {}", planet.name, step);
+                assert!(face.dot(&toward) > 0.99, "{} el día {}", planet.name, step);
+            }
+        }
+    }
 }
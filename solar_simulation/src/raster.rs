@@ -0,0 +1,200 @@
+use rayon::prelude::*;
+
+use crate::color::Color;
+use crate::framebuffer::{BlendMode, Framebuffer};
+use crate::shaders::ShaderFn;
+use crate::triangle::{self, calculate_bounding_box};
+use crate::vertex::Vertex;
+use crate::Uniforms;
+
+// Lado de cada tile en píxeles
+pub const TILE_SIZE: usize = 32;
+
+// Rasteriza triángulos ya proyectados a pantalla. Cada triángulo se asigna a
+// los tiles que toca su caja envolvente; después cada tile con triángulos se
+// sombrea en paralelo sobre una copia local de su parte del framebuffer, que
+// al final se vuelve a escribir. Tiles y no franjas: un cuerpo chico cae en
+// pocas filas de tiles y con franjas lo dibujarían uno o dos hilos. Dentro
+// de un tile los triángulos se procesan en el orden en que llegaron, así el
+// resultado es el mismo que el de un rasterizador secuencial (y las
+// superficies transparentes se mezclan en el orden en que se ordenaron).
+pub fn draw_triangles(
+    framebuffer: &mut Framebuffer,
+    triangles: &[[Vertex; 3]],
+    uniforms: &Uniforms,
+    fragment_shader: ShaderFn,
+    blend: BlendMode,
+) {
+    let width = framebuffer.width;
+    let height = framebuffer.height;
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let tiles_x = width.div_ceil(TILE_SIZE);
+    let tiles_y = height.div_ceil(TILE_SIZE);
+    let bins = bin_triangles(triangles, width, height, tiles_x, tiles_y);
+
+    let source: &Framebuffer = framebuffer;
+    let tiles: Vec<Tile> = bins
+        .par_iter()
+        .enumerate()
+        .filter(|(_, bin)| !bin.is_empty())
+        .map(|(index, bin)| {
+            let mut tile = Tile::read(source, (index % tiles_x) * TILE_SIZE, (index / tiles_x) * TILE_SIZE);
+            for &triangle_index in bin {
+                tile.draw(&triangles[triangle_index], uniforms, fragment_shader, blend);
+            }
+            tile
+        })
+        .collect();
+
+    for tile in &tiles {
+        tile.write(framebuffer);
+    }
+}
+
+// Copia de un rectángulo del framebuffer que un hilo puede modificar solo
+struct Tile {
+    x0: usize,
+    y0: usize,
+    width: usize,
+    height: usize,
+    colors: Vec<Color>,
+    depths: Vec<f32>,
+    emissive: Vec<Color>,
+}
+
+impl Tile {
+    fn read(framebuffer: &Framebuffer, x0: usize, y0: usize) -> Self {
+        let width = TILE_SIZE.min(framebuffer.width - x0);
+        let height = TILE_SIZE.min(framebuffer.height - y0);
+        let mut tile = Tile {
+            x0,
+            y0,
+            width,
+            height,
+            colors: Vec::with_capacity(width * height),
+            depths: Vec::with_capacity(width * height),
+            emissive: Vec::with_capacity(width * height),
+        };
+
+        for y in y0..y0 + height {
+            let row = y * framebuffer.width + x0..y * framebuffer.width + x0 + width;
+            tile.colors.extend_from_slice(&framebuffer.hdr[row.clone()]);
+            tile.depths.extend_from_slice(&framebuffer.zbuffer[row.clone()]);
+            tile.emissive.extend_from_slice(&framebuffer.emissive_buffer[row]);
+        }
+        tile
+    }
+
+    fn write(&self, framebuffer: &mut Framebuffer) {
+        for y in 0..self.height {
+            let start = (self.y0 + y) * framebuffer.width + self.x0;
+            let row = start..start + self.width;
+            let local = y * self.width..(y + 1) * self.width;
+            framebuffer.hdr[row.clone()].copy_from_slice(&self.colors[local.clone()]);
+            framebuffer.zbuffer[row.clone()].copy_from_slice(&self.depths[local.clone()]);
+            framebuffer.emissive_buffer[row].copy_from_slice(&self.emissive[local]);
+        }
+    }
+
+    fn draw(&mut self, [v1, v2, v3]: &[Vertex; 3], uniforms: &Uniforms, fragment_shader: ShaderFn, blend: BlendMode) {
+        let clip = (
+            self.x0 as i32,
+            self.y0 as i32,
+            (self.x0 + self.width - 1) as i32,
+            (self.y0 + self.height - 1) as i32,
+        );
+
+        triangle::triangle(v1, v2, v3, clip, |fragment| {
+            let x = fragment.position.x as usize - self.x0;
+            let y = fragment.position.y as usize - self.y0;
+            let index = y * self.width + x;
+
+            // Prueba de profundidad antes de sombrear. Lo opaco reemplaza
+            // también lo emisivo, para que un cuerpo tape el brillo de
+            // atrás; lo transparente no escribe profundidad.
+            if self.depths[index] > fragment.depth {
+                let output = fragment_shader(&fragment, uniforms);
+                self.colors[index] = blend.apply(self.colors[index], output.color);
+                if blend == BlendMode::Opaque {
+                    self.emissive[index] = output.emissive;
+                    self.depths[index] = fragment.depth;
+                } else {
+                    self.emissive[index] = self.emissive[index] + output.emissive * output.color.a;
+                }
+            }
+        });
+    }
+}
+
+// Lista de triángulos por tile (fila por fila), en orden de llegada
+fn bin_triangles(
+    triangles: &[[Vertex; 3]],
+    width: usize,
+    height: usize,
+    tiles_x: usize,
+    tiles_y: usize,
+) -> Vec<Vec<usize>> {
+    let mut bins = vec![Vec::new(); tiles_x * tiles_y];
+
+    for (index, [v1, v2, v3]) in triangles.iter().enumerate() {
+        // Un vértice NaN o infinito dejaría la caja en (0, 0, 0, 0) al
+        // convertirla a enteros y el triángulo caería en el primer tile
+        let corners = [&v1.transformed_position, &v2.transformed_position, &v3.transformed_position];
+        if corners.iter().any(|corner| !corner.iter().all(|value| value.is_finite())) {
+            continue;
+        }
+
+        let (min_x, min_y, max_x, max_y) = calculate_bounding_box(
+            &v1.transformed_position,
+            &v2.transformed_position,
+            &v3.transformed_position,
+        );
+
+        // Fuera de la pantalla: no toca ningún tile
+        if max_x < 0 || max_y < 0 || min_x >= width as i32 || min_y >= height as i32 {
+            continue;
+        }
+
+        let first_x = min_x.max(0) as usize / TILE_SIZE;
+        let first_y = min_y.max(0) as usize / TILE_SIZE;
+        let last_x = (max_x as usize).min(width - 1) / TILE_SIZE;
+        let last_y = (max_y as usize).min(height - 1) / TILE_SIZE;
+
+        for tile_y in first_y..=last_y {
+            for tile_x in first_x..=last_x {
+                bins[tile_y * tiles_x + tile_x].push(index);
+            }
+        }
+    }
+
+    bins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec3;
+
+    fn triangle(a: Vec3, b: Vec3, c: Vec3) -> [Vertex; 3] {
+        [a, b, c].map(|position| {
+            let mut vertex = Vertex::new_with_color(position, Color::black());
+            vertex.transformed_position = position;
+            vertex
+        })
+    }
+
+    #[test]
+    fn non_finite_triangles_are_not_binned() {
+        let triangles = [
+            triangle(Vec3::new(f32::NAN, 1.0, 0.0), Vec3::new(f32::NAN, 2.0, 0.0), Vec3::new(f32::NAN, 3.0, 0.0)),
+            triangle(Vec3::new(1.0, 1.0, 0.0), Vec3::new(f32::INFINITY, 1.0, 0.0), Vec3::new(1.0, 8.0, 0.0)),
+            triangle(Vec3::new(40.0, 40.0, 0.0), Vec3::new(50.0, 40.0, 0.0), Vec3::new(40.0, 50.0, f32::NAN)),
+            triangle(Vec3::new(1.0, 1.0, 0.0), Vec3::new(8.0, 1.0, 0.0), Vec3::new(1.0, 8.0, 0.0)),
+        ];
+        let bins = bin_triangles(&triangles, 64, 64, 2, 2);
+        assert_eq!(bins, vec![vec![3], vec![], vec![], vec![]]);
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use nalgebra_glm::Vec2;
+
+use crate::color::Color;
+use crate::fragment::Fragment;
+use crate::mesh::annulus;
+use crate::shaders::ShaderOutput;
+use crate::texture::{Texture, WrapMode};
+use crate::vertex::Vertex;
+use crate::Uniforms;
+
+// Segmentos de la malla del anillo y texeles del perfil generado
+const RING_SEGMENTS: usize = 128;
+const PROFILE_RESOLUTION: usize = 256;
+
+// Luz que atraviesa las partículas cuando se mira el lado no iluminado
+const TRANSLUCENCY: f32 = 0.3;
+const AMBIENT: f32 = 0.03;
+
+// Sistema de anillos en el plano ecuatorial de un planeta (el plano xz del
+// modelo, que sigue al polo), o inclinado `tilt` respecto a él. Los radios
+// están en radios del planeta; el perfil es una textura de una fila que va
+// del borde interior (u = 0) al exterior (u = 1), con la densidad en el alfa.
+pub struct Ring {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub tilt: f32,    // Inclinación respecto al ecuador (alrededor del eje x del modelo), en radianes
+    pub opacity: f32, // Multiplica la densidad del perfil
+    pub profile: Arc<Texture>,
+    pub vertices: Vec<Vertex>,
+}
+
+// Un punto del perfil radial: posición entre el borde interior (0) y el exterior (1)
+#[derive(Debug, Clone, Copy)]
+pub struct RingStop {
+    pub position: f32,
+    pub color: Color,
+    pub density: f32,
+}
+
+impl Ring {
+    pub fn new(inner_radius: f32, outer_radius: f32, tilt: f32, opacity: f32, profile: Arc<Texture>) -> Self {
+        // La esfera del modelo tiene radio 0.5: un radio de planeta son 0.5 unidades
+        let vertices = annulus(0.5 * inner_radius, 0.5 * outer_radius, RING_SEGMENTS);
+        Ring { inner_radius, outer_radius, tilt, opacity, profile, vertices }
+    }
+}
+
+// Perfil parecido al de Saturno: anillo C tenue, anillo B denso, la división
+// de Cassini casi vacía y el anillo A
+pub fn default_profile() -> Vec<RingStop> {
+    let stop = |position: f32, hex: u32, density: f32| RingStop { position, color: Color::from_hex(hex), density };
+    vec![
+        stop(0.0, 0x6E6558, 0.1),
+        stop(0.22, 0x8C7F6E, 0.25),
+        stop(0.26, 0xD2C2A2, 0.8),
+        stop(0.5, 0xE6D7B8, 0.95),
+        stop(0.6, 0xD8C8A8, 0.9),
+        stop(0.62, 0x3A342C, 0.05),
+        stop(0.67, 0x3A342C, 0.05),
+        stop(0.69, 0xC8B896, 0.65),
+        stop(0.9, 0xBFAF8C, 0.55),
+        stop(0.92, 0x7A6E5C, 0.15),
+        stop(0.94, 0xB4A484, 0.5),
+        stop(1.0, 0xA89878, 0.3),
+    ]
+}
+
+// Textura de una fila interpolando linealmente entre los puntos (ordenados por posición)
+pub fn profile_texture(stops: &[RingStop]) -> Texture {
+    let texels = (0..PROFILE_RESOLUTION)
+        .map(|i| {
+            let position = (i as f32 + 0.5) / PROFILE_RESOLUTION as f32;
+            let next = stops.iter().position(|stop| stop.position >= position);
+            let (color, density) = match next {
+                Some(0) => (stops[0].color, stops[0].density),
+                Some(index) => {
+                    let (a, b) = (&stops[index - 1], &stops[index]);
+                    let t = (position - a.position) / (b.position - a.position).max(f32::EPSILON);
+                    (a.color.lerp(&b.color, t), a.density + (b.density - a.density) * t)
+                }
+                None => {
+                    let last = stops.last().expect("el perfil necesita al menos un punto");
+                    (last.color, last.density)
+                }
+            };
+            color.with_alpha(density)
+        })
+        .collect();
+
+    let mut texture = Texture::from_pixels(PROFILE_RESOLUTION, 1, texels);
+    texture.wrap_u = WrapMode::ClampToEdge;
+    texture
+}
+
+// Color y densidad del perfil según la distancia al centro. La cara que da a
+// la luz se ilumina de frente; del otro lado sólo pasa una fracción. El
+// planeta proyecta su sombra con los mapas de sombras, como sobre cualquier
+// otra superficie.
+pub fn ring_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
+    let Some(profile) = &uniforms.texture else {
+        return Color::black().with_alpha(0.0).into();
+    };
+    let sample = profile.sample(Vec2::new(fragment.tex_coords.x, 0.5), fragment.tex_footprint);
+    let opacity = uniforms.shader_params.number("opacity", 1.0);
+
+    let normal = fragment.normal.normalize();
+    let to_camera = uniforms.camera_position - fragment.world_position;
+
+    let lit: f32 = uniforms
+        .lights
+        .iter()
+        .enumerate()
+        .map(|(index, light)| {
+            let to_light = (light.position - fragment.world_position).normalize();
+            let facing = normal.dot(&to_light);
+            let lit_side = if facing >= 0.0 { normal } else { -normal };
+            let transmitted = if facing * normal.dot(&to_camera) >= 0.0 { 1.0 } else { TRANSLUCENCY };
+
+            let visibility = uniforms
+                .shadow_maps
+                .get(index)
+                .map_or(1.0, |shadow_map| shadow_map.visibility(fragment.world_position, lit_side));
+            facing.abs() * transmitted * visibility * light.intensity
+        })
+        .sum();
+
+    (sample * lit.max(AMBIENT)).with_alpha(sample.a * opacity).into()
+}
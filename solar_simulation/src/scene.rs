@@ -0,0 +1,1087 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::f64::consts::TAU;
+use std::sync::Arc;
+
+use nalgebra_glm::Vec3;
+use serde::Deserialize;
+
+use crate::atmosphere::Atmosphere;
+use crate::bloom::Bloom;
+use crate::camera::Camera;
+use crate::clock::{self, SimClock};
+use crate::color::Color;
+use crate::display::{DisplayScale, ScaleMode};
+use crate::ephemeris::{Ephemeris, EphemerisError};
+use crate::framebuffer::BlendMode;
+use crate::nbody::GRAVITATIONAL_CONSTANT;
+use crate::orbit::{OrbitStyle, OrbitalElements};
+use crate::planet::Planet;
+use crate::ring::{self, Ring, RingStop};
+use crate::ship::Ship;
+use crate::shaders::{self, ParamKind, ShaderParam, ShaderParams};
+use crate::texture::{FilterMode, Texture, WrapMode};
+use crate::tonemap::{ToneMapping, ToneOperator};
+use crate::units::{self, AU_KM};
+
+// Descripción de una escena cargada desde un archivo TOML
+pub struct Scene {
+    pub name: String,
+    pub bodies: Vec<Planet>,
+    pub camera: Camera,
+    pub background_color: u32,
+    pub skybox: Skybox,
+    pub orbits: OrbitStyle,
+    pub clock: SimClock,
+    pub display: DisplayScale,
+    pub ship: Ship,
+    pub bloom: Bloom,
+    pub tone_mapping: ToneMapping,
+}
+
+pub struct Skybox {
+    pub star_count: usize,
+    pub color: u32,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(String, std::io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+    Texture(String, image::ImageError),
+    Ephemeris(String, EphemerisError),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(path, err) => write!(f, "no se pudo leer la escena '{}': {}", path, err),
+            SceneError::Parse(err) => write!(f, "error de sintaxis en la escena: {}", err),
+            SceneError::Invalid(message) => write!(f, "escena inválida: {}", message),
+            SceneError::Texture(path, err) => write!(f, "no se pudo cargar la textura '{}': {}", path, err),
+            SceneError::Ephemeris(path, err) => write!(f, "no se pudieron leer las efemérides '{}': {}", path, err),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<toml::de::Error> for SceneError {
+    fn from(err: toml::de::Error) -> Self {
+        SceneError::Parse(err)
+    }
+}
+
+// Formato del archivo. Los ángulos se escriben en grados.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    name: String,
+    #[serde(default = "default_background_color")]
+    background_color: String,
+    camera: CameraFile,
+    #[serde(default)]
+    skybox: SkyboxFile,
+    #[serde(default)]
+    orbits: OrbitsFile,
+    #[serde(default)]
+    clock: ClockFile,
+    #[serde(default)]
+    display: DisplayFile,
+    #[serde(default)]
+    ship: ShipFile,
+    #[serde(default)]
+    bloom: BloomFile,
+    #[serde(default)]
+    tone_mapping: ToneMappingFile,
+    #[serde(rename = "body")]
+    bodies: Vec<BodyFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraFile {
+    eye: [f32; 3],
+    #[serde(default)]
+    center: [f32; 3],
+    #[serde(default = "default_up")]
+    up: [f32; 3],
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SkyboxFile {
+    stars: usize,
+    color: String,
+}
+
+impl Default for SkyboxFile {
+    fn default() -> Self {
+        SkyboxFile { stars: 100, color: "#FFD966".to_string() }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OrbitsFile {
+    color: String,
+    #[serde(default = "default_orbit_fade")]
+    fade: bool,
+    #[serde(default = "default_orbit_thickness")]
+    thickness: f32,
+}
+
+impl Default for OrbitsFile {
+    fn default() -> Self {
+        OrbitsFile {
+            color: "#353535".to_string(),
+            fade: default_orbit_fade(),
+            thickness: default_orbit_thickness(),
+        }
+    }
+}
+
+// `epoch` es la fecha de time = 0 y `speed` los segundos de simulación por
+// segundo real
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct ClockFile {
+    epoch: String,
+    speed: f64,
+}
+
+impl Default for ClockFile {
+    fn default() -> Self {
+        ClockFile { epoch: "2000-01-01T12:00".to_string(), speed: 1.0 }
+    }
+}
+
+// Escala de pantalla (ver display.rs): `unit` son las UA que mide una unidad
+// de la escena dibujada
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct DisplayFile {
+    mode: String,
+    unit: f64,
+    exaggeration: f64,
+}
+
+impl Default for DisplayFile {
+    fn default() -> Self {
+        DisplayFile { mode: ScaleMode::Compressed.name().to_string(), unit: 1.0, exaggeration: 1000.0 }
+    }
+}
+
+// Nave (ver ship.rs), en unidades de la escena dibujada: `size` es su ancho,
+// `thrust` la aceleración del motor y `torque` la aceleración angular. Sin
+// `position` arranca delante de la cámara, mirando hacia donde mira ella.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct ShipFile {
+    position: Option<[f32; 3]>,
+    size: f32,
+    thrust: f32,
+    torque: f32,
+}
+
+impl Default for ShipFile {
+    fn default() -> Self {
+        ShipFile { position: None, size: 0.1, thrust: 0.2, torque: 1.5 }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct BloomFile {
+    threshold: f32,
+    sigma: f32,
+    strength: f32,
+}
+
+impl Default for BloomFile {
+    fn default() -> Self {
+        let bloom = Bloom::default();
+        BloomFile { threshold: bloom.threshold, sigma: bloom.sigma, strength: bloom.strength }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct ToneMappingFile {
+    operator: String,
+    exposure: f32,
+}
+
+impl Default for ToneMappingFile {
+    fn default() -> Self {
+        let tone_mapping = ToneMapping::default();
+        ToneMappingFile { operator: tone_mapping.operator.name().to_string(), exposure: tone_mapping.exposure }
+    }
+}
+
+// Radio y offset en km, masa en kg y período de rotación en horas
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BodyFile {
+    name: String,
+    // Cuerpo del que cuelga (declarado antes): su órbita y offset son relativos a él
+    parent: Option<String>,
+    #[serde(default)]
+    offset: [f32; 3],
+    radius: f32,
+    #[serde(default)]
+    mass: f32,
+    // Período de rotación sidéreo (0 = no gira). Con tidally_locked el cuerpo
+    // gira sincrónicamente y muestra siempre la misma cara a su padre.
+    #[serde(default)]
+    rotation_period: f32,
+    #[serde(default)]
+    tidally_locked: bool,
+    // Oblicuidad en grados: ángulo entre el polo y la normal de la órbita. Más
+    // de 90 es una rotación retrógrada (Venus, Urano).
+    #[serde(default)]
+    axial_tilt: f32,
+    // Longitud eclíptica (grados) hacia la que se inclina el polo
+    #[serde(default)]
+    pole_longitude: f32,
+    // Las estrellas declaran luminosity > 0 para iluminar a los demás cuerpos
+    #[serde(default)]
+    luminosity: f32,
+    // "opaque" (por defecto), "alpha", "additive", "multiply" o "subtract"
+    blend: Option<String>,
+    shader: ShaderFile,
+    texture: Option<TextureFile>,
+    atmosphere: Option<AtmosphereFile>,
+    ring: Option<RingFile>,
+    orbit: Option<OrbitFile>,
+    ephemeris: Option<EphemerisFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShaderFile {
+    name: String,
+    #[serde(default)]
+    params: HashMap<String, ParamFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TextureFile {
+    path: String,
+    #[serde(default = "default_filter")]
+    filter: String,
+    // Por defecto dependen del uso: ver load_texture
+    wrap_u: Option<String>,
+    wrap_v: Option<String>,
+}
+
+// Anillos, en el plano ecuatorial: radios en radios del planeta. El perfil se
+// describe con puntos (`profile`) o con una imagen (`texture`) cuyo eje x va
+// del borde interior al exterior; sin ninguno se usa un perfil tipo Saturno.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RingFile {
+    inner_radius: f32,
+    outer_radius: f32,
+    #[serde(default)]
+    tilt: f32, // Grados respecto al ecuador del planeta
+    #[serde(default = "default_ring_opacity")]
+    opacity: f32,
+    profile: Option<Vec<RingStopFile>>,
+    texture: Option<TextureFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RingStopFile {
+    position: f32,
+    color: String,
+    density: f32,
+}
+
+// Los campos omitidos toman los valores de Atmosphere::default (tipo Tierra)
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct AtmosphereFile {
+    thickness: f32,
+    rayleigh: [f32; 3],
+    rayleigh_scale_height: f32,
+    mie: f32,
+    mie_scale_height: f32,
+    mie_anisotropy: f32,
+    intensity: f32,
+    color: String,
+}
+
+impl Default for AtmosphereFile {
+    fn default() -> Self {
+        let atmosphere = Atmosphere::default();
+        AtmosphereFile {
+            thickness: atmosphere.thickness,
+            rayleigh: [atmosphere.rayleigh.x, atmosphere.rayleigh.y, atmosphere.rayleigh.z],
+            rayleigh_scale_height: atmosphere.rayleigh_scale_height,
+            mie: atmosphere.mie,
+            mie_scale_height: atmosphere.mie_scale_height,
+            mie_anisotropy: atmosphere.mie_anisotropy,
+            intensity: atmosphere.intensity,
+            color: "#FFFFFF".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ParamFile {
+    Number(f32),
+    Color(String),
+}
+
+// Efemérides en un archivo de JPL Horizons o CSV (ver ephemeris.rs). Las
+// posiciones son relativas al padre, como las órbitas.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EphemerisFile {
+    path: String,
+}
+
+// Semieje mayor en UA y movimiento medio en grados por día
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OrbitFile {
+    semi_major_axis: f32,
+    #[serde(default)]
+    eccentricity: f32,
+    #[serde(default)]
+    inclination: f32,
+    #[serde(default)]
+    longitude_ascending_node: f32,
+    #[serde(default)]
+    argument_periapsis: f32,
+    #[serde(default)]
+    mean_anomaly: f32,
+    // Si se omite, se deriva de la tercera ley de Kepler con la masa del padre
+    // o, si no tiene, con la del cuerpo más masivo
+    mean_motion: Option<f32>,
+}
+
+fn default_background_color() -> String {
+    "#060611".to_string()
+}
+
+fn default_up() -> [f32; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_filter() -> String {
+    "trilinear".to_string()
+}
+
+fn default_orbit_fade() -> bool {
+    true
+}
+
+fn default_orbit_thickness() -> f32 {
+    1.0
+}
+
+fn default_ring_opacity() -> f32 {
+    1.0
+}
+
+// Texturas ya cargadas, para no leer dos veces la misma imagen con el mismo muestreo
+type TextureCache = HashMap<(PathBuf, FilterMode, WrapMode, WrapMode), Arc<Texture>>;
+
+// Archivos que declara la escena: las rutas relativas se resuelven desde la
+// carpeta del archivo de la escena, no desde donde se ejecuta el programa
+struct Assets<'a> {
+    dir: &'a Path,
+    textures: TextureCache,
+}
+
+impl Assets<'_> {
+    fn path(&self, path: &str) -> PathBuf {
+        self.dir.join(path)
+    }
+}
+
+impl Scene {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .map_err(|err| SceneError::Io(path.display().to_string(), err))?;
+        Scene::parse(&source, path.parent().unwrap_or(Path::new("")))
+    }
+
+    // `dir` es la carpeta desde la que se resuelven las rutas de la escena
+    pub fn parse(source: &str, dir: &Path) -> Result<Self, SceneError> {
+        let file: SceneFile = toml::from_str(source)?;
+
+        if file.bodies.is_empty() {
+            return Err(SceneError::Invalid("la escena no declara ningún [[body]]".to_string()));
+        }
+
+        let camera = &file.camera;
+        if ![camera.eye, camera.center, camera.up].iter().flatten().all(|value| value.is_finite()) {
+            return Err(SceneError::Invalid("camera: eye, center y up deben ser números finitos".to_string()));
+        }
+        let camera_eye = vec3(camera.eye);
+        let camera_center = vec3(camera.center);
+        if (camera_eye - camera_center).magnitude() <= f32::EPSILON {
+            return Err(SceneError::Invalid("camera.eye y camera.center no pueden coincidir".to_string()));
+        }
+
+        let bloom = &file.bloom;
+        if !non_negative(bloom.threshold) || !non_negative(bloom.sigma) || !non_negative(bloom.strength) {
+            return Err(SceneError::Invalid(
+                "bloom: threshold, sigma y strength no pueden ser negativos".to_string(),
+            ));
+        }
+
+        let operator = ToneOperator::by_name(&file.tone_mapping.operator).ok_or_else(|| {
+            SceneError::Invalid(format!(
+                "tone_mapping.operator desconocido '{}' (clamp, reinhard, aces)",
+                file.tone_mapping.operator
+            ))
+        })?;
+        if !positive(file.tone_mapping.exposure) {
+            return Err(SceneError::Invalid("tone_mapping.exposure debe ser mayor que 0".to_string()));
+        }
+
+        if !positive(file.orbits.thickness) {
+            return Err(SceneError::Invalid("orbits.thickness debe ser mayor que 0".to_string()));
+        }
+
+        let epoch = clock::parse_date(&file.clock.epoch).ok_or_else(|| {
+            SceneError::Invalid(format!(
+                "clock.epoch: se esperaba una fecha \"AAAA-MM-DD[THH:MM]\", se encontró \"{}\"",
+                file.clock.epoch
+            ))
+        })?;
+        if !(clock::MIN_SPEED..=clock::MAX_SPEED).contains(&file.clock.speed) {
+            return Err(SceneError::Invalid(format!(
+                "clock.speed debe estar entre {} y {}",
+                clock::MIN_SPEED,
+                clock::MAX_SPEED
+            )));
+        }
+
+        let clock = SimClock::new(epoch, file.clock.speed);
+        let primary_mass = file.bodies.iter().map(|body| body.mass).fold(0.0, f32::max);
+
+        let mut assets = Assets { dir, textures: TextureCache::new() };
+        let mut bodies: Vec<Planet> = Vec::with_capacity(file.bodies.len());
+        for body in &file.bodies {
+            if bodies.iter().any(|other| other.name == body.name) {
+                return Err(invalid_body(&body.name, "el nombre está repetido"));
+            }
+            let parent = match &body.parent {
+                Some(name) => Some(bodies.iter().position(|other| &other.name == name).ok_or_else(|| {
+                    invalid_body(&body.name, &format!("el padre '{}' no existe o se declara después", name))
+                })?),
+                None => None,
+            };
+            // Las órbitas de los hijos se derivan de la masa del padre
+            let central_mass = parent.map_or(primary_mass, |index| bodies[index].mass);
+            bodies.push(parse_body(body, parent, central_mass, &clock, &mut assets)?);
+        }
+
+        let scale_mode = ScaleMode::by_name(&file.display.mode).ok_or_else(|| {
+            SceneError::Invalid(format!(
+                "display.mode desconocido '{}' (true, exaggerated, compressed)",
+                file.display.mode
+            ))
+        })?;
+        if !positive(file.display.unit) || !non_negative(file.display.exaggeration - 1.0) {
+            return Err(SceneError::Invalid(
+                "display: unit debe ser mayor que 0 y exaggeration al menos 1".to_string(),
+            ));
+        }
+        let ship = &file.ship;
+        if !positive(ship.size) || !positive(ship.thrust) || !positive(ship.torque) {
+            return Err(SceneError::Invalid("ship: size, thrust y torque deben ser mayores que 0".to_string()));
+        }
+        let forward = (camera_center - camera_eye).normalize();
+        if ship.position.is_some_and(|position| !position.iter().all(|value| value.is_finite())) {
+            return Err(SceneError::Invalid("ship.position debe tener números finitos".to_string()));
+        }
+        let ship_position = ship.position.map_or(camera_eye + forward * 10.0 * ship.size, vec3);
+
+        let display = DisplayScale::new(scale_mode, file.display.unit * AU_KM, file.display.exaggeration, &bodies);
+
+        Ok(Scene {
+            name: file.name,
+            bodies,
+            camera: Camera::new(camera_eye, camera_center, vec3(camera.up)),
+            background_color: parse_color(&file.background_color, "background_color")?,
+            skybox: Skybox {
+                star_count: file.skybox.stars,
+                color: parse_color(&file.skybox.color, "skybox.color")?,
+            },
+            orbits: OrbitStyle {
+                color: Color::from_hex(parse_color(&file.orbits.color, "orbits.color")?),
+                fade: file.orbits.fade,
+                thickness: file.orbits.thickness,
+            },
+            clock,
+            display,
+            ship: Ship::new(ship_position, ship_position + forward, ship.size, ship.thrust, ship.torque),
+            bloom: Bloom { threshold: bloom.threshold, sigma: bloom.sigma, strength: bloom.strength },
+            tone_mapping: ToneMapping { operator, exposure: file.tone_mapping.exposure },
+        })
+    }
+}
+
+fn parse_body(
+    body: &BodyFile,
+    parent: Option<usize>,
+    central_mass: f32,
+    clock: &SimClock,
+    assets: &mut Assets,
+) -> Result<Planet, SceneError> {
+    if !positive(body.radius) {
+        return Err(invalid_body(&body.name, "radius debe ser mayor que 0"));
+    }
+    if !non_negative(body.mass) {
+        return Err(invalid_body(&body.name, "mass no puede ser negativa"));
+    }
+    if !non_negative(body.luminosity) {
+        return Err(invalid_body(&body.name, "luminosity no puede ser negativa"));
+    }
+    if !non_negative(body.rotation_period) {
+        return Err(invalid_body(&body.name, "rotation_period no puede ser negativo (para girar al revés, axial_tilt > 90)"));
+    }
+    if body.tidally_locked && body.rotation_period > 0.0 {
+        return Err(invalid_body(&body.name, "acepta rotation_period o tidally_locked, no los dos"));
+    }
+    if !(0.0..=180.0).contains(&body.axial_tilt) {
+        return Err(invalid_body(&body.name, "axial_tilt debe estar entre 0 y 180"));
+    }
+    if !body.pole_longitude.is_finite() || !body.offset.iter().all(|value| value.is_finite()) {
+        return Err(invalid_body(&body.name, "pole_longitude y offset deben ser números finitos"));
+    }
+
+    let shader = shaders::shader_by_name(&body.shader.name).ok_or_else(|| {
+        invalid_body(
+            &body.name,
+            &format!("shader desconocido '{}' (disponibles: {})", body.shader.name, shaders::SHADER_NAMES.join(", ")),
+        )
+    })?;
+
+    let shader_params = parse_shader_params(&body.name, &body.shader)?;
+
+    let blend = match &body.blend {
+        Some(name) => BlendMode::by_name(name).ok_or_else(|| {
+            invalid_body(
+                &body.name,
+                &format!("blend desconocido '{}' (opaque, alpha, additive, multiply, subtract)", name),
+            )
+        })?,
+        None => BlendMode::Opaque,
+    };
+
+    let texture = match &body.texture {
+        // Los mapas equirectangulares se repiten en longitud y no en latitud
+        Some(texture) => Some(load_texture(&body.name, texture, (WrapMode::Repeat, WrapMode::ClampToEdge), assets)?),
+        None if body.shader.name == "texture" => {
+            return Err(invalid_body(&body.name, "el shader 'texture' necesita una tabla texture = { path = ... }"));
+        }
+        None => None,
+    };
+
+    let atmosphere = match &body.atmosphere {
+        Some(atmosphere) => Some(parse_atmosphere(&body.name, atmosphere)?),
+        None => None,
+    };
+
+    let ring = match &body.ring {
+        Some(ring) => Some(parse_ring(&body.name, ring, assets)?),
+        None => None,
+    };
+
+    let orbit = match &body.orbit {
+        Some(orbit) => parse_orbit(&body.name, orbit, central_mass + body.mass)?,
+        None => OrbitalElements::stationary(),
+    };
+
+    let ephemeris = match &body.ephemeris {
+        Some(_) if body.orbit.is_some() => {
+            return Err(invalid_body(&body.name, "acepta orbit o ephemeris, no los dos"));
+        }
+        Some(file) => Some(load_ephemeris(file, clock, assets)?),
+        None => None,
+    };
+
+    Ok(Planet {
+        name: body.name.clone(),
+        parent,
+        offset: vec3(body.offset),
+        radius: body.radius,
+        orbit,
+        ephemeris,
+        pole: pole_direction(body.orbit.as_ref().map(|_| &orbit), body.axial_tilt, body.pole_longitude),
+        rotation_speed: if body.rotation_period > 0.0 {
+            (TAU / (body.rotation_period as f64 * units::HOUR_SECONDS)) as f32
+        } else {
+            0.0
+        },
+        tidally_locked: body.tidally_locked,
+        rotation: 0.0,
+        mass: body.mass,
+        position: Vec3::new(0.0, 0.0, 0.0),
+        velocity: Vec3::new(0.0, 0.0, 0.0),
+        display_position: Vec3::new(0.0, 0.0, 0.0),
+        display_radius: 0.0,
+        shader,
+        shader_params,
+        texture,
+        luminosity: body.luminosity,
+        blend,
+        atmosphere,
+        ring,
+    })
+}
+
+// Cada parámetro debe ser uno de los que lee el shader y tener su tipo
+fn parse_shader_params(name: &str, file: &ShaderFile) -> Result<ShaderParams, SceneError> {
+    let accepted = shaders::shader_param_kinds(&file.name);
+
+    let mut params = ShaderParams::default();
+    for (key, value) in &file.params {
+        let Some(&(_, kind)) = accepted.iter().find(|(param, _)| param == key) else {
+            let names: Vec<&str> = accepted.iter().map(|(param, _)| *param).collect();
+            let names = if names.is_empty() { "ninguno".to_string() } else { names.join(", ") };
+            return Err(invalid_body(
+                name,
+                &format!("el shader '{}' no tiene el parámetro '{}' (acepta: {})", file.name, key, names),
+            ));
+        };
+
+        let param = match (value, kind) {
+            (ParamFile::Number(number), ParamKind::Number) => ShaderParam::Number(*number),
+            (ParamFile::Color(hex), ParamKind::Color) => ShaderParam::Color(Color::from_hex(parse_color(hex, key)?)),
+            (_, ParamKind::Number) => {
+                return Err(invalid_body(name, &format!("el parámetro '{}' debe ser un número", key)));
+            }
+            (_, ParamKind::Color) => {
+                return Err(invalid_body(name, &format!("el parámetro '{}' debe ser un color \"#RRGGBB\"", key)));
+            }
+        };
+        params.insert(key, param);
+    }
+    Ok(params)
+}
+
+fn parse_atmosphere(name: &str, file: &AtmosphereFile) -> Result<Atmosphere, SceneError> {
+    if !positive(file.thickness) {
+        return Err(invalid_body(name, "atmosphere.thickness debe ser mayor que 0"));
+    }
+    if !positive(file.rayleigh_scale_height) || !positive(file.mie_scale_height) {
+        return Err(invalid_body(name, "las alturas de escala de atmosphere deben ser mayores que 0"));
+    }
+    let rayleigh = file.rayleigh.iter().all(|&coefficient| non_negative(coefficient));
+    if !rayleigh || !non_negative(file.mie) || !non_negative(file.intensity) {
+        return Err(invalid_body(name, "atmosphere: rayleigh, mie e intensity no pueden ser negativos"));
+    }
+    if !(file.mie_anisotropy > -1.0 && file.mie_anisotropy < 1.0) {
+        return Err(invalid_body(name, "atmosphere.mie_anisotropy debe estar en (-1, 1)"));
+    }
+
+    Ok(Atmosphere {
+        thickness: file.thickness,
+        rayleigh: vec3(file.rayleigh),
+        rayleigh_scale_height: file.rayleigh_scale_height,
+        mie: file.mie,
+        mie_scale_height: file.mie_scale_height,
+        mie_anisotropy: file.mie_anisotropy,
+        intensity: file.intensity,
+        color: Color::from_hex(parse_color(&file.color, "atmosphere.color")?),
+    })
+}
+
+fn parse_ring(name: &str, file: &RingFile, assets: &mut Assets) -> Result<Ring, SceneError> {
+    if !positive(file.inner_radius) || !positive(file.outer_radius - file.inner_radius) {
+        return Err(invalid_body(name, "ring necesita 0 < inner_radius < outer_radius"));
+    }
+    if !(0.0..=1.0).contains(&file.opacity) {
+        return Err(invalid_body(name, "ring.opacity debe estar en [0, 1]"));
+    }
+    if !file.tilt.is_finite() {
+        return Err(invalid_body(name, "ring.tilt debe ser un número finito"));
+    }
+
+    let profile = match (&file.profile, &file.texture) {
+        (Some(_), Some(_)) => {
+            return Err(invalid_body(name, "ring acepta profile o texture, no los dos"));
+        }
+        (None, Some(texture)) => load_texture(name, texture, (WrapMode::ClampToEdge, WrapMode::ClampToEdge), assets)?,
+        (Some(stops), None) => Arc::new(ring::profile_texture(&parse_ring_profile(name, stops)?)),
+        (None, None) => Arc::new(ring::profile_texture(&ring::default_profile())),
+    };
+
+    Ok(Ring::new(file.inner_radius, file.outer_radius, file.tilt.to_radians(), file.opacity, profile))
+}
+
+fn parse_ring_profile(name: &str, stops: &[RingStopFile]) -> Result<Vec<RingStop>, SceneError> {
+    if stops.is_empty() {
+        return Err(invalid_body(name, "ring.profile necesita al menos un punto"));
+    }
+
+    let mut profile = Vec::with_capacity(stops.len());
+    for stop in stops {
+        if !(0.0..=1.0).contains(&stop.position) || !(0.0..=1.0).contains(&stop.density) {
+            return Err(invalid_body(name, "ring.profile: position y density deben estar en [0, 1]"));
+        }
+        profile.push(RingStop {
+            position: stop.position,
+            color: Color::from_hex(parse_color(&stop.color, "ring.profile.color")?),
+            density: stop.density,
+        });
+    }
+    profile.sort_by(|a, b| a.position.total_cmp(&b.position));
+    Ok(profile)
+}
+
+fn load_ephemeris(file: &EphemerisFile, clock: &SimClock, assets: &Assets) -> Result<Ephemeris, SceneError> {
+    let path = assets.path(&file.path);
+    let mut ephemeris = Ephemeris::load(&path).map_err(|err| SceneError::Ephemeris(path.display().to_string(), err))?;
+    ephemeris.convert(clock.epoch);
+    Ok(ephemeris)
+}
+
+// `wrap` son los modos que se usan si el archivo no los declara
+fn load_texture(
+    name: &str,
+    file: &TextureFile,
+    wrap: (WrapMode, WrapMode),
+    assets: &mut Assets,
+) -> Result<Arc<Texture>, SceneError> {
+    let filter = match file.filter.as_str() {
+        "nearest" => FilterMode::Nearest,
+        "bilinear" => FilterMode::Bilinear,
+        "trilinear" => FilterMode::Trilinear,
+        other => {
+            return Err(invalid_body(name, &format!("texture.filter desconocido '{}' (nearest, bilinear, trilinear)", other)));
+        }
+    };
+    let wrap_u = match &file.wrap_u {
+        Some(value) => parse_wrap(name, value, "texture.wrap_u")?,
+        None => wrap.0,
+    };
+    let wrap_v = match &file.wrap_v {
+        Some(value) => parse_wrap(name, value, "texture.wrap_v")?,
+        None => wrap.1,
+    };
+
+    let path = assets.path(&file.path);
+    let key = (path.clone(), filter, wrap_u, wrap_v);
+    if let Some(texture) = assets.textures.get(&key) {
+        return Ok(texture.clone());
+    }
+
+    let mut texture = Texture::load(&path).map_err(|err| SceneError::Texture(path.display().to_string(), err))?;
+    texture.filter = filter;
+    texture.wrap_u = wrap_u;
+    texture.wrap_v = wrap_v;
+
+    let texture = Arc::new(texture);
+    assets.textures.insert(key, texture.clone());
+    Ok(texture)
+}
+
+fn parse_wrap(name: &str, value: &str, field: &str) -> Result<WrapMode, SceneError> {
+    match value {
+        "repeat" => Ok(WrapMode::Repeat),
+        "mirror" => Ok(WrapMode::MirroredRepeat),
+        "clamp" => Ok(WrapMode::ClampToEdge),
+        other => Err(invalid_body(name, &format!("{} desconocido '{}' (repeat, mirror, clamp)", field, other))),
+    }
+}
+
+fn parse_orbit(name: &str, orbit: &OrbitFile, central_mass: f32) -> Result<OrbitalElements, SceneError> {
+    if !non_negative(orbit.semi_major_axis) {
+        return Err(invalid_body(name, "orbit.semi_major_axis no puede ser negativo"));
+    }
+    let angles = [orbit.inclination, orbit.longitude_ascending_node, orbit.argument_periapsis, orbit.mean_anomaly];
+    if !angles.iter().chain(&orbit.mean_motion).all(|value| value.is_finite()) {
+        return Err(invalid_body(name, "los ángulos y mean_motion de orbit deben ser números finitos"));
+    }
+    if !(0.0..1.0).contains(&orbit.eccentricity) {
+        return Err(invalid_body(name, "orbit.eccentricity debe estar en [0, 1)"));
+    }
+
+    let semi_major_axis = orbit.semi_major_axis as f64 * AU_KM;
+    let mean_motion = match orbit.mean_motion {
+        Some(mean_motion) => units::degrees_per_day(mean_motion as f64),
+        None if orbit.semi_major_axis > 0.0 && central_mass > 0.0 => {
+            (GRAVITATIONAL_CONSTANT * central_mass as f64 / semi_major_axis.powi(3)).sqrt()
+        }
+        None => {
+            return Err(invalid_body(name, "orbit.mean_motion es obligatorio si no hay masa central (la del padre o la del cuerpo más masivo)"));
+        }
+    };
+
+    Ok(OrbitalElements {
+        semi_major_axis: semi_major_axis as f32,
+        eccentricity: orbit.eccentricity,
+        inclination: orbit.inclination.to_radians(),
+        longitude_ascending_node: orbit.longitude_ascending_node.to_radians(),
+        argument_periapsis: orbit.argument_periapsis.to_radians(),
+        mean_anomaly_epoch: orbit.mean_anomaly.to_radians(),
+        mean_motion: mean_motion as f32,
+    })
+}
+
+// Polo de un cuerpo: la normal de su órbita (o el eje z, la normal de la
+// eclíptica, si no tiene) inclinada `tilt` grados hacia la longitud eclíptica
+// `longitude`
+fn pole_direction(orbit: Option<&OrbitalElements>, tilt: f32, longitude: f32) -> Vec3 {
+    let normal = match orbit {
+        Some(orbit) => {
+            let (sin_i, cos_i) = orbit.inclination.sin_cos();
+            let (sin_o, cos_o) = orbit.longitude_ascending_node.sin_cos();
+            Vec3::new(sin_o * sin_i, -cos_o * sin_i, cos_i)
+        }
+        None => Vec3::z(),
+    };
+
+    let (sin_l, cos_l) = longitude.to_radians().sin_cos();
+    let lean = Vec3::new(cos_l, sin_l, 0.0);
+    let lean = lean - normal * lean.dot(&normal);
+    if lean.magnitude() <= 1e-6 {
+        return normal;
+    }
+
+    let (sin_t, cos_t) = tilt.to_radians().sin_cos();
+    (normal * cos_t + lean.normalize() * sin_t).normalize()
+}
+
+// Acepta colores "#RRGGBB" o "0xRRGGBB"
+fn parse_color(value: &str, field: &str) -> Result<u32, SceneError> {
+    let digits = value
+        .strip_prefix('#')
+        .or_else(|| value.strip_prefix("0x"))
+        .unwrap_or(value);
+
+    if digits.len() != 6 {
+        return Err(SceneError::Invalid(format!("{}: se esperaba un color \"#RRGGBB\", se encontró \"{}\"", field, value)));
+    }
+
+    u32::from_str_radix(digits, 16)
+        .map_err(|_| SceneError::Invalid(format!("{}: color inválido \"{}\"", field, value)))
+}
+
+// Las comparaciones con NaN siempre son falsas: `x <= 0.0` lo dejaría pasar
+fn positive(value: impl Into<f64>) -> bool {
+    let value = value.into();
+    value.is_finite() && value > 0.0
+}
+
+fn non_negative(value: impl Into<f64>) -> bool {
+    let value = value.into();
+    value.is_finite() && value >= 0.0
+}
+
+fn invalid_body(name: &str, message: &str) -> SceneError {
+    SceneError::Invalid(format!("cuerpo '{}': {}", name, message))
+}
+
+fn vec3(v: [f32; 3]) -> Vec3 {
+    Vec3::new(v[0], v[1], v[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = r#"
+name = "Prueba"
+camera = { eye = [0.0, 0.0, 5.0] }
+"#;
+
+    const SUN: &str = r#"
+[[body]]
+name = "Sol"
+radius = 696000.0
+mass = 1.989e30
+luminosity = 1.0
+shader = { name = "star" }
+"#;
+
+    fn parse(bodies: &str) -> Result<Scene, SceneError> {
+        Scene::parse(&format!("{}{}", HEADER, bodies), Path::new(""))
+    }
+
+    // El mensaje de error, que debe ser de validación y mencionar `expected`
+    fn invalid(bodies: &str, expected: &str) -> String {
+        match parse(bodies) {
+            Err(SceneError::Invalid(message)) => {
+                assert!(message.contains(expected), "'{}' no menciona '{}'", message, expected);
+                message
+            }
+            Err(err) => panic!("se esperaba un error de validación, se obtuvo: {}", err),
+            Ok(_) => panic!("la escena debería ser inválida ({})", expected),
+        }
+    }
+
+    fn body(fields: &str) -> String {
+        format!("{}\n[[body]]\nname = \"Planeta\"\nshader = {{ name = \"moon\" }}\n{}\n", SUN, fields)
+    }
+
+    #[test]
+    fn bundled_scenes_load() {
+        for name in ["solar_system", "trappist1", "binary_star"] {
+            let path = format!("assets/scenes/{}.toml", name);
+            if let Err(err) = Scene::load(&path) {
+                panic!("{}: {}", path, err);
+            }
+        }
+    }
+
+    #[test]
+    fn ring_tilt_is_optional() {
+        let ring = |fields: &str| {
+            let scene = parse(&body(&format!("radius = 6371.0\nmass = 5.97e24\nring = {{ inner_radius = 1.2, outer_radius = 2.0{} }}", fields)));
+            scene.unwrap().bodies[1].ring.as_ref().unwrap().tilt
+        };
+        assert_eq!(ring(""), 0.0);
+        assert!((ring(", tilt = 27.0") - 27.0_f32.to_radians()).abs() < 1e-6);
+        invalid(&body("radius = 6371.0\nmass = 5.97e24\nring = { inner_radius = 1.2, outer_radius = 2.0, tilt = nan }"), "ring.tilt");
+    }
+
+    #[test]
+    fn minimal_scene_parses() {
+        let scene = parse(&body("radius = 6371.0\nmass = 5.97e24\norbit = { semi_major_axis = 1.0 }")).unwrap();
+        assert_eq!(scene.bodies.len(), 2);
+        assert_eq!(scene.bodies[1].name, "Planeta");
+        // Sin mean_motion se deriva de la masa del Sol: un año
+        let period_days = TAU / scene.bodies[1].orbit.mean_motion as f64 / units::DAY_SECONDS;
+        assert!((period_days - 365.25).abs() < 1.0, "período {}", period_days);
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let scenes = [
+            format!("{}color = \"#FFFFFF\"\n{}", HEADER, SUN),
+            format!("{}{}", HEADER, SUN.replace("luminosity", "brightness")),
+            format!("{}{}", HEADER, body("radius = 1.0\norbit = { semi_major_axis = 1.0, period = 2.0 }")),
+        ];
+        for source in scenes {
+            assert!(matches!(Scene::parse(&source, Path::new("")), Err(SceneError::Parse(_))), "{}", source);
+        }
+    }
+
+    #[test]
+    fn rejects_missing_bodies() {
+        assert!(matches!(Scene::parse(HEADER, Path::new("")), Err(SceneError::Parse(_))));
+        assert!(matches!(Scene::parse(&format!("{}body = []", HEADER), Path::new("")), Err(SceneError::Invalid(_))));
+    }
+
+    #[test]
+    fn rejects_non_positive_radius_and_negative_mass() {
+        for radius in ["0.0", "-1.0", "nan", "inf"] {
+            invalid(&body(&format!("radius = {}", radius)), "radius");
+        }
+        for mass in ["-1.0", "nan"] {
+            invalid(&body(&format!("radius = 1.0\nmass = {}", mass)), "mass");
+        }
+    }
+
+    #[test]
+    fn rejects_nan_in_orbits_and_camera() {
+        invalid(&body("radius = 1.0\norbit = { semi_major_axis = nan }"), "semi_major_axis");
+        invalid(&body("radius = 1.0\norbit = { semi_major_axis = 1.0, eccentricity = nan }"), "eccentricity");
+        invalid(&body("radius = 1.0\norbit = { semi_major_axis = 1.0, inclination = nan }"), "orbit");
+
+        let source = format!("name = \"Prueba\"\ncamera = {{ eye = [nan, 0.0, 5.0] }}\n{}", SUN);
+        assert!(matches!(Scene::parse(&source, Path::new("")), Err(SceneError::Invalid(_))));
+    }
+
+    #[test]
+    fn rejects_orbit_with_ephemeris() {
+        invalid(
+            &body("radius = 1.0\norbit = { semi_major_axis = 1.0 }\nephemeris = { path = \"tabla.csv\" }"),
+            "orbit o ephemeris",
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_shader() {
+        let message = invalid(&SUN.replace("\"star\"", "\"estrella\""), "shader desconocido 'estrella'");
+        assert!(message.contains("star"), "debería listar los shaders disponibles: {}", message);
+    }
+
+    #[test]
+    fn rejects_unknown_shader_params() {
+        let sun = SUN.replace("{ name = \"star\" }", "{ name = \"star\", params = { brillo = 0.5 } }");
+        let message = invalid(&sun, "'brillo'");
+        assert!(message.contains("glow"), "debería listar los parámetros aceptados: {}", message);
+
+        let moon = body("radius = 1.0").replace("{ name = \"moon\" }", "{ name = \"moon\", params = { glow = 1.0 } }");
+        invalid(&moon, "acepta: ninguno");
+    }
+
+    #[test]
+    fn rejects_shader_params_of_the_wrong_type() {
+        let color_as_number = SUN.replace("{ name = \"star\" }", "{ name = \"star\", params = { dark_color = 0.5 } }");
+        invalid(&color_as_number, "debe ser un color");
+
+        let number_as_color = SUN.replace("{ name = \"star\" }", "{ name = \"star\", params = { glow = \"#FFFFFF\" } }");
+        invalid(&number_as_color, "debe ser un número");
+
+        let bad_color = SUN.replace("{ name = \"star\" }", "{ name = \"star\", params = { dark_color = \"#FFF\" } }");
+        invalid(&bad_color, "#RRGGBB");
+    }
+
+    #[test]
+    fn accepts_declared_shader_params() {
+        let sun = SUN.replace("{ name = \"star\" }", "{ name = \"star\", params = { glow = 2, dark_color = \"#102030\" } }");
+        let scene = parse(&sun).unwrap();
+        let params = &scene.bodies[0].shader_params;
+        assert_eq!(params.number("glow", 0.0), 2.0);
+        assert_eq!(params.color("dark_color", Color::black()).to_hex(), 0x102030);
+    }
+
+    #[test]
+    fn rejects_repeated_names_and_missing_parents() {
+        invalid(&format!("{}{}", SUN, SUN), "repetido");
+        invalid(&body("radius = 1.0\nparent = \"Tierra\""), "'Tierra' no existe");
+    }
+
+    #[test]
+    fn resolves_ephemerides_from_the_scene_directory() {
+        let source = format!("{}{}", HEADER, body("radius = 1.0\nephemeris = { path = \"circular.csv\" }"));
+        let scene = Scene::parse(&source, Path::new("tests/fixtures")).unwrap();
+        let ephemeris = scene.bodies[1].ephemeris.as_ref().unwrap();
+        // Las muestras quedan en segundos desde la época del reloj y en km
+        assert_eq!(ephemeris.start(), 0.0);
+        assert!((ephemeris.samples[0].position.x - AU_KM).abs() < 1e-3);
+
+        assert!(matches!(Scene::parse(&source, Path::new("")), Err(SceneError::Ephemeris(..))));
+    }
+
+    #[test]
+    fn resolves_textures_from_the_scene_directory() {
+        let dir = std::env::temp_dir().join(format!("solar_simulation_scene_{}", std::process::id()));
+        fs::create_dir_all(dir.join("textures")).unwrap();
+        image::RgbImage::from_pixel(4, 2, image::Rgb([10, 20, 30])).save(dir.join("textures/mapa.png")).unwrap();
+
+        let source = format!(
+            "{}{}",
+            HEADER,
+            body("radius = 1.0\ntexture = { path = \"textures/mapa.png\" }").replace("\"moon\"", "\"texture\"")
+        );
+        let scene_path = dir.join("escena.toml");
+        fs::write(&scene_path, source).unwrap();
+
+        let loaded = Scene::load(&scene_path);
+        let missing = Scene::parse(&fs::read_to_string(&scene_path).unwrap(), Path::new("no/existe"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(loaded.unwrap().bodies[1].texture.is_some());
+        assert!(matches!(missing, Err(SceneError::Texture(..))));
+    }
+}
@@ -1,14 +1,129 @@
 
-use nalgebra_glm::{Vec3, Vec4, Mat3, dot, mat4_to_mat3};
+use nalgebra_glm::{Vec3, Vec4, Mat3, mat4_to_mat3};
 use crate::vertex::Vertex;
 use crate::Uniforms;
 use crate::fragment::Fragment;
 use crate::color::Color;
-use std::f32::consts::PI;
+use crate::light;
 use rand::Rng;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
+use std::collections::HashMap;
 
+pub type ShaderFn = fn(&Fragment, &Uniforms) -> ShaderOutput;
+
+// Resultado de un fragment shader: el color visible y la luz que emite (negro
+// si no emite). Lo emisivo alimenta el bloom.
+#[derive(Debug, Clone, Copy)]
+pub struct ShaderOutput {
+    pub color: Color,
+    pub emissive: Color,
+}
+
+impl From<Color> for ShaderOutput {
+    fn from(color: Color) -> Self {
+        ShaderOutput { color, emissive: Color::black() }
+    }
+}
+
+// Nombres con los que las escenas pueden referirse a cada shader
+pub const SHADER_NAMES: [&str; 14] = [
+    "texture", "star", "earth", "moon", "meteor", "rocky_planet", "gas_giant", "ringed_planet",
+    "lava", "cloud", "cellular", "dalmata", "black_and_white", "ship",
+];
+
+pub fn shader_by_name(name: &str) -> Option<ShaderFn> {
+    let shader: ShaderFn = match name {
+        "texture" => texture_shader,
+        "star" => star_shader,
+        "earth" => earth_shader,
+        "moon" => moon_shader,
+        "meteor" => meteor_shader,
+        "rocky_planet" => rocky_planet_shader,
+        "gas_giant" => gas_giant_shader,
+        "ringed_planet" => ringed_planet_shader,
+        "lava" => lava_shader,
+        "cloud" => cloud_shader,
+        "cellular" => cellular_shader,
+        "dalmata" => dalmata_shader,
+        "black_and_white" => black_and_white,
+        "ship" => ship_shader,
+        _ => return None,
+    };
+    Some(shader)
+}
+
+// Tipo de valor que espera un parámetro de shader
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    Number,
+    Color,
+}
+
+// Parámetros que lee cada shader, para que la escena rechace los demás en vez
+// de ignorarlos al dibujar
+pub fn shader_param_kinds(name: &str) -> &'static [(&'static str, ParamKind)] {
+    match name {
+        "star" => &[("bright_color", ParamKind::Color), ("dark_color", ParamKind::Color), ("glow", ParamKind::Number)],
+        "earth" => &[
+            ("land_color", ParamKind::Color),
+            ("ocean_color", ParamKind::Color),
+            ("cloud_intensity", ParamKind::Number),
+            ("zoom", ParamKind::Number),
+            ("land_threshold", ParamKind::Number),
+            ("cloud_threshold", ParamKind::Number),
+        ],
+        "rocky_planet" => &[
+            ("dark_color", ParamKind::Color),
+            ("light_color", ParamKind::Color),
+            ("crack_color", ParamKind::Color),
+        ],
+        "gas_giant" => &[
+            ("light_band_color", ParamKind::Color),
+            ("dark_band_color", ParamKind::Color),
+            ("swirl_color", ParamKind::Color),
+        ],
+        "ringed_planet" => &[("light_band_color", ParamKind::Color), ("dark_band_color", ParamKind::Color)],
+        "cloud" => &[("cloud_opacity", ParamKind::Number), ("sky_opacity", ParamKind::Number)],
+        "ship" => &[("hull_color", ParamKind::Color), ("panel_color", ParamKind::Color)],
+        _ => &[],
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ShaderParam {
+    Number(f32),
+    Color(Color),
+}
+
+// Parámetros por cuerpo que la escena le pasa a su shader
+#[derive(Debug, Clone, Default)]
+pub struct ShaderParams {
+    values: HashMap<String, ShaderParam>,
+}
+
+impl ShaderParams {
+    pub fn insert(&mut self, name: &str, value: ShaderParam) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn number(&self, name: &str, default: f32) -> f32 {
+        match self.values.get(name) {
+            Some(ShaderParam::Number(value)) => *value,
+            _ => default,
+        }
+    }
+
+    pub fn color(&self, name: &str, default: Color) -> Color {
+        match self.values.get(name) {
+            Some(ShaderParam::Color(value)) => *value,
+            _ => default,
+        }
+    }
+}
+
+// Transforma el vértice a espacio de recorte. La división por w se hace en
+// `project_to_screen`, después del recorte contra el frustum.
 pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     let position = Vec4::new(
         vertex.position.x,
@@ -17,17 +132,8 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
         1.0
     );
 
-    let transformed = uniforms.projection_matrix * uniforms.view_matrix * uniforms.model_matrix * position;
-
-    let w = transformed.w;
-    let transformed_position = Vec4::new(
-        transformed.x / w,
-        transformed.y / w,
-        transformed.z / w,
-        1.0
-    );
-
-    let screen_position = uniforms.viewport_matrix * transformed_position;
+    let world_position = uniforms.model_matrix * position;
+    let clip_position = uniforms.projection_matrix * uniforms.view_matrix * world_position;
 
     let model_mat3 = mat4_to_mat3(&uniforms.model_matrix);
     let normal_matrix = model_mat3.transpose().try_inverse().unwrap_or(Mat3::identity());
@@ -39,21 +145,29 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
         normal: vertex.normal,
         tex_coords: vertex.tex_coords,
         color: vertex.color,
-        transformed_position: Vec3::new(screen_position.x, screen_position.y, screen_position.z),
-        transformed_normal: transformed_normal
+        clip_position,
+        world_position: Vec3::new(world_position.x, world_position.y, world_position.z),
+        transformed_position: Vec3::new(clip_position.x, clip_position.y, clip_position.z),
+        transformed_normal
     }
 }
 
-pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    black_and_white(fragment, uniforms)
-    // dalmata_shader(fragment, uniforms)
-    // cloud_shader(fragment, uniforms)
-    // cellular_shader(fragment, uniforms)
-    // lava_shader(fragment, uniforms)
+// División de perspectiva y viewport: de espacio de recorte a pantalla
+pub fn project_to_screen(vertex: &mut Vertex, uniforms: &Uniforms) {
+    let w = vertex.clip_position.w;
+    let ndc_position = Vec4::new(
+        vertex.clip_position.x / w,
+        vertex.clip_position.y / w,
+        vertex.clip_position.z / w,
+        1.0
+    );
+
+    let screen_position = uniforms.viewport_matrix * ndc_position;
+    vertex.transformed_position = Vec3::new(screen_position.x, screen_position.y, screen_position.z);
 }
 
-fn black_and_white(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    let seed = uniforms.time as f32 * fragment.vertex_position.y * fragment.vertex_position.x;
+fn black_and_white(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
+    let seed = uniforms.time * fragment.vertex_position.y * fragment.vertex_position.x;
   
     let mut rng = StdRng::seed_from_u64(seed.abs() as u64);
   
@@ -65,10 +179,10 @@ fn black_and_white(fragment: &Fragment, uniforms: &Uniforms) -> Color {
       Color::new(255, 255, 255)
     };
   
-    black_or_white * fragment.intensity
+    (black_or_white * light::diffuse(fragment, uniforms, 0.0)).into()
 }
   
-fn dalmata_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+fn dalmata_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
     let zoom = 100.0;
     let ox = 0.0;
     let oy = 0.0;
@@ -90,23 +204,24 @@ fn dalmata_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
       base_color
     };
   
-    noise_color * fragment.intensity
+    (noise_color * light::diffuse(fragment, uniforms, 0.0)).into()
 }
   
-fn cloud_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+fn cloud_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
     let zoom = 100.0;  // to move our values 
     let ox = 100.0; // offset x in the noise map
     let oy = 100.0;
     let x = fragment.vertex_position.x;
     let y = fragment.vertex_position.y;
-    let t = uniforms.time as f32 * 0.5;
+    let t = uniforms.time * 0.5;
   
     let noise_value = uniforms.noise.get_noise_2d(x * zoom + ox + t, y * zoom + oy);
   
     // Define cloud threshold and colors
     let cloud_threshold = 0.5; // Adjust this value to change cloud density
-    let cloud_color = Color::new(255, 255, 255); // White for clouds
-    let sky_color = Color::new(30, 97, 145); // Sky blue
+    let params = &uniforms.shader_params;
+    let cloud_color = Color::new(255, 255, 255).with_alpha(params.number("cloud_opacity", 1.0)); // White for clouds
+    let sky_color = Color::new(30, 97, 145).with_alpha(params.number("sky_opacity", 1.0)); // Sky blue
   
     // Determine if the pixel is part of a cloud or sky
     let noise_color = if noise_value > cloud_threshold {
@@ -115,10 +230,10 @@ fn cloud_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
       sky_color
     };
   
-    noise_color * fragment.intensity
+    (noise_color * light::diffuse(fragment, uniforms, 0.0)).into()
 }
   
-fn cellular_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+fn cellular_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
     let zoom = 30.0;  // Zoom factor to adjust the scale of the cell pattern
     let ox = 50.0;    // Offset x in the noise map
     let oy = 50.0;    // Offset y in the noise map
@@ -146,10 +261,10 @@ fn cellular_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
     };
   
     // Adjust intensity to simulate lighting effects (optional)
-    final_color * fragment.intensity
+    (final_color * light::diffuse(fragment, uniforms, 0.0)).into()
 }
   
-fn lava_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+fn lava_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
     // Base colors for the lava effect
     let bright_color = Color::new(255, 240, 0); // Bright orange (lava-like)
     let dark_color = Color::new(130, 20, 0);   // Darker red-orange
@@ -164,7 +279,7 @@ fn lava_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
     // Base frequency and amplitude for the pulsating effect
     let base_frequency = 0.2;
     let pulsate_amplitude = 0.5;
-    let t = uniforms.time as f32 * 0.01;
+    let t = uniforms.time * 0.01;
   
     // Pulsate on the z-axis to change spot size
     let pulsate = (t * base_frequency).sin() * pulsate_amplitude;
@@ -186,12 +301,13 @@ fn lava_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
     // Use lerp for color blending based on noise value
     let color = dark_color.lerp(&bright_color, noise_value);
   
-    color * fragment.intensity
+    (color * light::diffuse(fragment, uniforms, 0.0)).into()
 }
 
-pub fn star_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-  let bright_color = Color::new(255, 240, 0); // Naranja brillante (lava)
-  let dark_color = Color::new(130, 20, 0);   // Rojo oscuro
+pub fn star_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
+  let params = &uniforms.shader_params;
+  let bright_color = params.color("bright_color", Color::new(255, 240, 0)); // Naranja brillante (lava)
+  let dark_color = params.color("dark_color", Color::new(130, 20, 0));      // Rojo oscuro
 
   let position = Vec3::new(
       fragment.vertex_position.x,
@@ -201,7 +317,7 @@ pub fn star_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
 
   let base_frequency = 0.2;
   let pulsate_amplitude = 0.5;
-  let t = uniforms.time as f32 * 0.01;
+  let t = uniforms.time * 0.01;
 
   let pulsate = (t * base_frequency).sin() * pulsate_amplitude;
 
@@ -214,32 +330,32 @@ pub fn star_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
 
   let color = dark_color.lerp(&bright_color, noise_value);
 
-  // Condición para agregar el color al buffer emisivo si es suficientemente brillante
-  if noise_value > 0.7 {
-      color // Devuelve el color brillante para el buffer principal
-  } else {
-      color * 0.5 // Devuelve un color más oscuro para zonas no emisivas
-  }
+  let color = if noise_value > 0.7 { color } else { color * 0.5 };
+
+  // Toda la superficie emite con el color brillante; `glow` regula el resplandor
+  let glow = params.number("glow", 0.8);
+  ShaderOutput { color, emissive: bright_color * glow }
 }
 
-pub fn earth_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+pub fn earth_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
     // Configuración de colores y propiedades de las nubes
-    let earth_green = Color::new(34, 139, 34);
-    let ocean_blue = Color::new(70, 130, 180);
+    let params = &uniforms.shader_params;
+    let earth_green = params.color("land_color", Color::new(34, 139, 34));
+    let ocean_blue = params.color("ocean_color", Color::new(70, 130, 180));
     let cloud_color = Color::new(255, 255, 255);
-    let cloud_intensity = 0.3; // Ajusta este valor para cambiar la intensidad de las nubes
+    let cloud_intensity = params.number("cloud_intensity", 0.3); // Ajusta este valor para cambiar la intensidad de las nubes
 
     // Ajusta el zoom y desplazamiento
-    let zoom = 100.0;
+    let zoom = params.number("zoom", 100.0);
     let x = fragment.vertex_position.x;
     let y = fragment.vertex_position.y;
-    let t = uniforms.time as f32 * 0.01; // Más lento que el Sol
+    let t = uniforms.time * 0.01; // Más lento que el Sol
 
     // Genera el valor de ruido para tierra/agua
     let surface_noise_value = uniforms.noise.get_noise_2d(x * zoom + t, y * zoom + t);
 
     // Umbral para dividir tierra y océano
-    let threshold = 0.3;
+    let threshold = params.number("land_threshold", 0.3);
     let mut color = if surface_noise_value > threshold {
         earth_green
     } else {
@@ -250,20 +366,20 @@ pub fn earth_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
     let cloud_noise_value = uniforms.noise.get_noise_2d((x + t * 0.1) * zoom, (y + t * 0.1) * zoom);
 
     // Agrega las nubes si el ruido de nubes está por encima de un umbral
-    let cloud_threshold = 0.6;
+    let cloud_threshold = params.number("cloud_threshold", 0.6);
     if cloud_noise_value > cloud_threshold {
         color = color.lerp(&cloud_color, cloud_intensity);
     }
 
     // Ajusta la intensidad para darle efecto de sombreado (como luz y sombra)
-    color * fragment.intensity
+    (color * light::diffuse(fragment, uniforms, 0.0)).into()
 }
 
 
 
-pub fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+pub fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
   let position = fragment.vertex_position;
-  let time = uniforms.time as f32 * 0.001;
+  let time = uniforms.time * 0.001;
 
   // 1. Capa de superficie rocosa con cráteres
   let rock_noise_value = (uniforms.noise.get_noise_3d(position.x * 2.0, position.y * 2.0, position.z * 2.0) + 1.0) / 2.0;
@@ -294,17 +410,13 @@ pub fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
   };
 
   // 4. Iluminación para darle profundidad
-  let light_dir = Vec3::new(1.0, 1.0, 1.0).normalize(); // Dirección de la luz
-  let normal = fragment.normal.normalize();
-  let intensity = normal.dot(&light_dir).max(0.3); // Ajuste de intensidad mínima
-  let shaded_color = meteor_color * intensity;
-
-  shaded_color
+  let intensity = light::diffuse(fragment, uniforms, 0.3); // Ajuste de intensidad mínima
+  (meteor_color * intensity).into()
 }
 
-pub fn meteor_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+pub fn meteor_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
   let position = fragment.vertex_position;
-  let time = uniforms.time as f32 * 0.001;
+  let time = uniforms.time * 0.001;
 
   // Capa base de color roca
   let rock_noise_value = (uniforms.noise.get_noise_3d(position.x * 3.0, position.y * 3.0, position.z * 3.0) + 1.0) / 2.0;
@@ -335,30 +447,26 @@ pub fn meteor_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
   };
 
   // Iluminación para agregar profundidad
-  let light_dir = Vec3::new(1.0, 1.0, 1.0).normalize(); // Dirección de la luz
-  let normal = fragment.normal.normalize();
-  let intensity = normal.dot(&light_dir).max(0.3); // Ajuste de intensidad mínima
-  let shaded_color = meteor_color * intensity;
-
-  shaded_color
+  let intensity = light::diffuse(fragment, uniforms, 0.3); // Ajuste de intensidad mínima
+  (meteor_color * intensity).into()
 }
 
 
-pub fn rocky_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+pub fn rocky_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
+  let params = &uniforms.shader_params;
   let position = fragment.vertex_position;
-  let time = uniforms.time as f32 * 0.001;
 
   // Capa base de color roca con ruido de baja frecuencia
   let base_noise = (uniforms.noise.get_noise_3d(position.x * 2.0, position.y * 2.0, position.z * 2.0) + 1.0) / 2.0;
   let base_color = if base_noise > 0.5 {
-      Color::new(139, 69, 19) // Marrón oscuro para zonas rocosas
+      params.color("dark_color", Color::new(139, 69, 19)) // Marrón oscuro para zonas rocosas
   } else {
-      Color::new(205, 133, 63) // Marrón claro para variación
+      params.color("light_color", Color::new(205, 133, 63)) // Marrón claro para variación
   };
 
   // Capa de sombras y grietas (ruido de alta frecuencia)
   let crack_noise = (uniforms.noise.get_noise_3d(position.x * 10.0, position.y * 10.0, position.z * 10.0) + 1.0) / 2.0;
-  let crack_color = Color::new(80, 40, 20); // Color más oscuro para grietas y sombras
+  let crack_color = params.color("crack_color", Color::new(80, 40, 20)); // Color más oscuro para grietas y sombras
   let surface_color = if crack_noise < 0.3 {
       crack_color
   } else {
@@ -366,66 +474,91 @@ pub fn rocky_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
   };
 
   // Simulación de sombras e iluminación en el planeta rocoso
-  let light_dir = Vec3::new(1.0, 0.8, 0.6).normalize();
-  let normal = fragment.normal.normalize();
-  let intensity = normal.dot(&light_dir).max(0.2); // Ajuste de intensidad mínima
+  let intensity = light::diffuse(fragment, uniforms, 0.2); // Ajuste de intensidad mínima
 
-  let shaded_color = surface_color * intensity;
+  (surface_color * intensity).into()
+}
 
-  shaded_color
+// Casco metálico de la nave: paneles de dos tonos y un brillo especular de
+// cada luz, para que se note hacia dónde gira
+pub fn ship_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
+  let params = &uniforms.shader_params;
+  let position = fragment.vertex_position;
+
+  let panel_noise = uniforms.noise.get_noise_3d(position.x * 8.0, position.y * 8.0, position.z * 8.0);
+  let hull_color = if panel_noise > 0.2 {
+      params.color("panel_color", Color::new(110, 118, 130))
+  } else {
+      params.color("hull_color", Color::new(170, 176, 186))
+  };
+
+  let normal = fragment.normal.normalize();
+  let view_dir = (uniforms.camera_position - fragment.world_position).normalize();
+  let specular: f32 = uniforms
+      .lights
+      .iter()
+      .map(|light| {
+          let light_dir = (light.position - fragment.world_position).normalize();
+          let half = (light_dir + view_dir).normalize();
+          normal.dot(&half).max(0.0).powf(32.0) * light.intensity
+      })
+      .sum();
+
+  let intensity = light::diffuse(fragment, uniforms, 0.15);
+  (hull_color * intensity + Color::new(255, 255, 255) * (0.5 * specular)).into()
 }
 
-pub fn gas_giant_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+pub fn gas_giant_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
+  let params = &uniforms.shader_params;
   let position = fragment.vertex_position;
-  let time = uniforms.time as f32 * 0.002;
+  let time = uniforms.time * 0.002;
 
   // Capa de bandas de gas utilizando un patrón de ruido ondulante
   let band_noise = uniforms.noise.get_noise_2d(position.x * 0.5, position.y * 5.0 + time).abs();
   let band_color = if band_noise < 0.5 {
-      Color::new(255, 204, 153) // Color más claro para las bandas
+      params.color("light_band_color", Color::new(255, 204, 153)) // Color más claro para las bandas
   } else {
-      Color::new(204, 153, 102) // Color más oscuro para las bandas
+      params.color("dark_band_color", Color::new(204, 153, 102)) // Color más oscuro para las bandas
   };
 
   // Capa de remolinos o turbulencias
   let swirl_noise = uniforms.noise.get_noise_3d(position.x * 2.0, position.y * 2.0, time).abs();
   let swirl_color = if swirl_noise > 0.6 {
-      Color::new(255, 255, 204) // Remolinos en color claro
+      params.color("swirl_color", Color::new(255, 255, 204)) // Remolinos en color claro
   } else {
       band_color
   };
 
   // Efecto de sombreado suave
-  let light_dir = Vec3::new(1.0, -0.5, 0.3).normalize();
-  let normal = fragment.normal.normalize();
-  let intensity = normal.dot(&light_dir).max(0.4); // Ajuste de intensidad mínima
+  let intensity = light::diffuse(fragment, uniforms, 0.4); // Ajuste de intensidad mínima
 
-  let shaded_color = swirl_color * intensity;
+  (swirl_color * intensity).into()
+}
+
+// Muestrea la textura del cuerpo (magenta si no tiene, para que se note)
+pub fn texture_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
+  let albedo = match &uniforms.texture {
+    Some(texture) => texture.sample(fragment.tex_coords, fragment.tex_footprint),
+    None => Color::new(255, 0, 255),
+  };
 
-  shaded_color
+  let intensity = light::diffuse(fragment, uniforms, 0.3); // Ajuste de intensidad mínima
+  (albedo * intensity).into()
 }
 
-pub fn ringed_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
+// Cuerpo de un planeta con anillos: bandas suaves por latitud. Los anillos
+// son una malla aparte (ver ring.rs), declarada con `ring` en la escena.
+pub fn ringed_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> ShaderOutput {
+  let params = &uniforms.shader_params;
   let position = fragment.vertex_position;
-  let distance_from_center = (position.x.powi(2) + position.y.powi(2)).sqrt();
-
-  // Define los radios internos y externos de los anillos
-  let inner_radius = 1.2;
-  let outer_radius = 1.5;
-
-  // Si el fragmento está dentro del rango de los anillos, aplica un color de anillo
-  let ring_color = if distance_from_center > inner_radius && distance_from_center < outer_radius {
-      let ring_pattern = (distance_from_center * 10.0).sin().abs(); // Patrón de bandas
-      if ring_pattern > 0.5 {
-          Color::new(200, 200, 200) // Color claro para la banda
-      } else {
-          Color::new(100, 100, 100) // Color oscuro para la banda
-      }
-  } else {
-      // Color del planeta base en el centro
-      Color::new(80, 50, 20) // Color del planeta
-  };
 
-  ring_color
+  // Bandas por latitud, con el borde ondulado por el ruido
+  let wobble = uniforms.noise.get_noise_2d(position.x * 3.0, position.z * 3.0) * 0.05;
+  let band = ((position.y + wobble) * 18.0).sin() * 0.5 + 0.5;
+  let light_color = params.color("light_band_color", Color::new(226, 206, 160));
+  let dark_color = params.color("dark_band_color", Color::new(176, 146, 100));
+  let color = dark_color.lerp(&light_color, band);
+
+  (color * light::diffuse(fragment, uniforms, 0.3)).into()
 }
 
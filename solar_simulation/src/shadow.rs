@@ -0,0 +1,168 @@
+use std::f32::consts::FRAC_PI_2;
+
+use nalgebra_glm::{look_at, perspective, Mat4, Vec3, Vec4};
+use rayon::prelude::*;
+
+use crate::clipping;
+use crate::triangle;
+use crate::vertex::Vertex;
+
+// Resolución de cada cara del cubo de sombras
+pub const SHADOW_MAP_SIZE: usize = 512;
+// Plano cercano de las caras; el lejano se ajusta a los cuerpos
+const NEAR_PLANE: f32 = 0.05;
+
+// Dirección y vector "arriba" de cada cara: +X, -X, +Y, -Y, +Z, -Z
+const FACES: [(Vec3, Vec3); 6] = [
+    (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+    (Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+    (Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)),
+    (Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)),
+    (Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 1.0, 0.0)),
+    (Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 1.0, 0.0)),
+];
+
+// Mapa de sombras cúbico de una luz puntual. Cada cara guarda, por texel, la
+// distancia desde la luz a la superficie más cercana en esa dirección.
+pub struct ShadowMap {
+    light_position: Vec3,
+    size: usize,
+    faces: Vec<ShadowFace>,
+}
+
+struct ShadowFace {
+    view_projection: Mat4,
+    distances: Vec<f32>,
+}
+
+// Un cuerpo que proyecta sombra: su matriz de modelo y su malla
+pub struct ShadowCaster<'a> {
+    pub model_matrix: Mat4,
+    pub vertices: &'a [Vertex],
+}
+
+impl ShadowMap {
+    pub fn render(light_position: Vec3, size: usize, casters: &[ShadowCaster]) -> Self {
+        // Las caras guardan distancias, no profundidad: el far sólo tiene que
+        // alcanzar al cuerpo más lejano, por grande que sea la escena
+        let far = casters.iter().map(|caster| caster.reach(light_position)).fold(1.0, f32::max);
+        let projection = perspective(1.0, FRAC_PI_2, NEAR_PLANE, far * 1.01);
+
+        let mut faces: Vec<ShadowFace> = FACES
+            .iter()
+            .map(|(direction, up)| ShadowFace {
+                view_projection: projection * look_at(&light_position, &(light_position + direction), up),
+                distances: vec![f32::INFINITY; size * size],
+            })
+            .collect();
+
+        faces.par_iter_mut().for_each(|face| {
+            for caster in casters {
+                face.draw(light_position, size, caster);
+            }
+        });
+
+        ShadowMap { light_position, size, faces }
+    }
+
+    // Fracción de luz que llega a `position` (0 = en sombra, 1 = iluminado).
+    // Se promedian 3x3 texeles (PCF) para suavizar el borde de la sombra.
+    pub fn visibility(&self, position: Vec3, normal: Vec3) -> f32 {
+        let to_point = position - self.light_position;
+        let distance = to_point.magnitude();
+        if distance <= f32::EPSILON {
+            return 1.0;
+        }
+
+        // Desplaza el punto sobre la normal según el tamaño de un texel a esa
+        // distancia, para que la superficie no se sombree a sí misma
+        let texel_size = 2.0 * distance / self.size as f32;
+        let position = position + normal.normalize() * texel_size * 1.5;
+        let to_point = position - self.light_position;
+        let distance = to_point.magnitude();
+
+        let face = &self.faces[face_index(&to_point)];
+        let clip = face.view_projection * Vec4::new(position.x, position.y, position.z, 1.0);
+        let x = (clip.x / clip.w * 0.5 + 0.5) * self.size as f32;
+        let y = (clip.y / clip.w * 0.5 + 0.5) * self.size as f32;
+
+        let bias = texel_size;
+        let last = self.size as i32 - 1;
+        let mut lit = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let tx = (x as i32 + dx).clamp(0, last) as usize;
+                let ty = (y as i32 + dy).clamp(0, last) as usize;
+                if distance - bias <= face.distances[ty * self.size + tx] {
+                    lit += 1;
+                }
+            }
+        }
+
+        lit as f32 / 9.0
+    }
+}
+
+impl ShadowCaster<'_> {
+    // Distancia de `point` al punto más lejano del cuerpo (acotada con su
+    // esfera envolvente)
+    fn reach(&self, point: Vec3) -> f32 {
+        let center = self.model_matrix.column(3).xyz();
+        let scale = (0..3).map(|axis| self.model_matrix.column(axis).xyz().magnitude()).fold(0.0, f32::max);
+        let radius = self.vertices.iter().map(|vertex| vertex.position.magnitude()).fold(0.0, f32::max);
+        (center - point).magnitude() + radius * scale
+    }
+}
+
+impl ShadowFace {
+    fn draw(&mut self, light_position: Vec3, size: usize, caster: &ShadowCaster) {
+        let view_projection = self.view_projection;
+        let transform = |vertex: &Vertex| {
+            let world = caster.model_matrix * Vec4::new(vertex.position.x, vertex.position.y, vertex.position.z, 1.0);
+            Vertex {
+                clip_position: view_projection * world,
+                world_position: Vec3::new(world.x, world.y, world.z),
+                ..Vertex::default()
+            }
+        };
+
+        let last = size as i32 - 1;
+        for tri in caster.vertices.chunks_exact(3) {
+            let (v1, v2, v3) = (transform(&tri[0]), transform(&tri[1]), transform(&tri[2]));
+
+            for mut clipped in clipping::clip_triangle(&v1, &v2, &v3) {
+                for vertex in clipped.iter_mut() {
+                    let clip = vertex.clip_position;
+                    vertex.transformed_position = Vec3::new(
+                        (clip.x / clip.w * 0.5 + 0.5) * size as f32,
+                        (clip.y / clip.w * 0.5 + 0.5) * size as f32,
+                        clip.z / clip.w,
+                    );
+                }
+
+                let [v1, v2, v3] = &clipped;
+                triangle::triangle(v1, v2, v3, (0, 0, last, last), |fragment| {
+                    let index = fragment.position.y as usize * size + fragment.position.x as usize;
+                    let distance = (fragment.world_position - light_position).magnitude();
+                    if distance < self.distances[index] {
+                        self.distances[index] = distance;
+                    }
+                });
+            }
+        }
+    }
+}
+
+// Cara del cubo hacia la que apunta `direction` (el eje de mayor magnitud)
+fn face_index(direction: &Vec3) -> usize {
+    let (x, y, z) = (direction.x.abs(), direction.y.abs(), direction.z.abs());
+    if x >= y && x >= z {
+        if direction.x > 0.0 { 0 } else { 1 }
+    } else if y >= z {
+        if direction.y > 0.0 { 2 } else { 3 }
+    } else if direction.z > 0.0 {
+        4
+    } else {
+        5
+    }
+}
@@ -0,0 +1,239 @@
+use nalgebra_glm::{quat_angle_axis, quat_normalize, quat_rotate_vec3, quat_to_mat4, to_quat, Mat4, Quat, Vec3};
+
+use crate::camera::Camera;
+use crate::framebuffer::Framebuffer;
+use crate::shaders::{ship_shader, ShaderParams};
+use crate::vertex::Vertex;
+use crate::Uniforms;
+
+// Los propulsores laterales (RCS) empujan menos que el motor principal
+const RCS_FRACTION: f32 = 0.25;
+// Cámara de persecución, en tamaños de la nave: detrás y un poco arriba,
+// mirando a un punto delante de la nave
+const CHASE_OFFSET: Vec3 = Vec3::new(0.0, 0.6, 3.0);
+const CHASE_TARGET: Vec3 = Vec3::new(0.0, 0.3, -2.0);
+// Qué tan rápido alcanza la cámara su posición (1/s): con la nave acelerando
+// queda un poco atrás y se siente la inercia
+const CHASE_STIFFNESS: f32 = 6.0;
+
+// Lo que pide el piloto en un cuadro, entre -1 y 1 por eje
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShipControls {
+    pub thrust: Vec3, // Derecha, arriba y adelante, en el marco de la nave
+    pub torque: Vec3, // Cabeceo, guiñada y alabeo (alrededor de x, y, z de la nave)
+    pub stabilize: bool, // Frena la rotación
+    pub brake: bool,     // Empuja en contra de la velocidad
+}
+
+// Nave con seis grados de libertad. Vive en la escena dibujada (no en km):
+// se pilotea entre los cuerpos tal como se ven. Sin propulsión conserva su
+// velocidad y su rotación. En su marco, adelante es -z y arriba es +y; la
+// velocidad angular está en ese marco.
+#[derive(Debug, Clone)]
+pub struct Ship {
+    pub position: Vec3,
+    pub orientation: Quat,
+    pub velocity: Vec3,
+    pub angular_velocity: Vec3, // rad/s
+    pub size: f32,              // Ancho de la nave en unidades de la escena
+    pub thrust: f32,            // Aceleración del motor principal (unidades/s²)
+    pub torque: f32,            // Aceleración angular de los RCS (rad/s²)
+}
+
+impl Ship {
+    // Nave quieta en `position`, apuntando hacia `target`
+    pub fn new(position: Vec3, target: Vec3, size: f32, thrust: f32, torque: f32) -> Self {
+        let forward = (target - position).normalize();
+        let up = if forward.cross(&Vec3::y()).magnitude() > 1e-3 { Vec3::y() } else { Vec3::z() };
+        let right = forward.cross(&up).normalize();
+        let up = right.cross(&forward);
+        let rotation = Mat4::new(
+            right.x, up.x, -forward.x, 0.0,
+            right.y, up.y, -forward.y, 0.0,
+            right.z, up.z, -forward.z, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        );
+
+        Ship {
+            position,
+            orientation: to_quat(&rotation),
+            velocity: Vec3::zeros(),
+            angular_velocity: Vec3::zeros(),
+            size,
+            thrust,
+            torque,
+        }
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        quat_rotate_vec3(&self.orientation, &Vec3::new(0.0, 0.0, -1.0))
+    }
+
+    pub fn up(&self) -> Vec3 {
+        quat_rotate_vec3(&self.orientation, &Vec3::y())
+    }
+
+    // Avanza `dt` segundos reales: la nave no sigue al reloj de la
+    // simulación, así se puede pilotear con el tiempo en pausa
+    pub fn update(&mut self, controls: &ShipControls, dt: f32) {
+        // Rotación: los RCS aceleran en el marco de la nave; estabilizar frena
+        // cada eje a la misma aceleración
+        self.angular_velocity += controls.torque * self.torque * dt;
+        if controls.stabilize {
+            let speed = self.angular_velocity.magnitude();
+            let reduced = (speed - self.torque * dt).max(0.0);
+            self.angular_velocity *= if speed > 0.0 { reduced / speed } else { 0.0 };
+        }
+
+        let angle = self.angular_velocity.magnitude() * dt;
+        if angle > 0.0 {
+            let spin = quat_angle_axis(angle, &self.angular_velocity);
+            self.orientation = quat_normalize(&(self.orientation * spin));
+        }
+
+        // Traslación: motor principal hacia adelante o atrás, RCS a los costados
+        let local = Vec3::new(
+            controls.thrust.x * RCS_FRACTION,
+            controls.thrust.y * RCS_FRACTION,
+            -controls.thrust.z,
+        );
+        self.velocity += quat_rotate_vec3(&self.orientation, &local) * self.thrust * dt;
+        if controls.brake {
+            let speed = self.velocity.magnitude();
+            let reduced = (speed - self.thrust * dt).max(0.0);
+            self.velocity *= if speed > 0.0 { reduced / speed } else { 0.0 };
+        }
+
+        self.position += self.velocity * dt;
+    }
+
+    // Lleva la cámara hacia su lugar detrás de la nave
+    pub fn chase(&self, camera: &mut Camera, dt: f32) {
+        let to_world = |offset: &Vec3| self.position + quat_rotate_vec3(&self.orientation, offset) * self.size;
+        let eye = to_world(&CHASE_OFFSET);
+        let blend = 1.0 - (-CHASE_STIFFNESS * dt).exp();
+
+        camera.eye += (eye - camera.eye) * blend;
+        camera.center = to_world(&CHASE_TARGET);
+        camera.up = self.up();
+        camera.has_changed = true;
+    }
+
+    // Escala el modelo para que su ancho sea `size`
+    pub fn model_matrix(&self, model_radius: f32) -> Mat4 {
+        crate::create_model_matrix(self.position, 0.5 * self.size / model_radius, Vec3::zeros())
+            * quat_to_mat4(&self.orientation)
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer, vertex_array: &[Vertex], uniforms: &mut Uniforms) {
+        let model_radius = vertex_array.iter().map(|vertex| vertex.position.magnitude()).fold(f32::EPSILON, f32::max);
+        uniforms.model_matrix = self.model_matrix(model_radius);
+        uniforms.shader_params = ShaderParams::default();
+        uniforms.texture = None;
+        crate::render_celestial_body(framebuffer, vertex_array, uniforms, ship_shader);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Nave apuntando en una dirección cualquiera, para que los ejes locales
+    // no coincidan con los del mundo
+    fn ship() -> Ship {
+        Ship::new(Vec3::new(1.0, 2.0, 3.0), Vec3::new(-4.0, 5.0, -2.0), 1.0, 2.0, 1.0)
+    }
+
+    fn right(ship: &Ship) -> Vec3 {
+        quat_rotate_vec3(&ship.orientation, &Vec3::x())
+    }
+
+    fn assert_close(a: Vec3, b: Vec3, message: &str) {
+        assert!((a - b).magnitude() < 1e-5, "{message}: {a:?} contra {b:?}");
+    }
+
+    #[test]
+    fn new_ship_points_at_its_target() {
+        let ship = ship();
+        let forward = (Vec3::new(-4.0, 5.0, -2.0) - ship.position).normalize();
+        assert_close(ship.forward(), forward, "adelante");
+        assert_close(quat_rotate_vec3(&ship.orientation, &Vec3::new(0.0, 0.0, -1.0)), forward, "orientación * -z");
+    }
+
+    #[test]
+    fn thrust_pushes_along_the_ship_axes() {
+        let dt = 0.1;
+        let mut ship = ship();
+        let forward = ship.forward();
+        ship.update(&ShipControls { thrust: Vec3::z(), ..Default::default() }, dt);
+        assert_close(ship.velocity, forward * ship.thrust * dt, "motor principal");
+
+        // Los RCS empujan a los costados, con menos fuerza
+        let mut ship = self::ship();
+        let (right, up) = (right(&ship), ship.up());
+        ship.update(&ShipControls { thrust: Vec3::new(1.0, -1.0, 0.0), ..Default::default() }, dt);
+        assert_close(ship.velocity, (right - up) * RCS_FRACTION * ship.thrust * dt, "RCS");
+        // Sin empuje conserva la velocidad
+        let velocity = ship.velocity;
+        ship.update(&ShipControls::default(), dt);
+        assert_close(ship.velocity, velocity, "inercia");
+    }
+
+    #[test]
+    fn torque_rotates_about_the_ship_axes() {
+        let dt = 0.1;
+        // Un cuadro con el control y diez sin él: gira torque * dt² por cuadro
+        let turn = |controls: ShipControls| {
+            let mut ship = ship();
+            let before = (right(&ship), ship.up(), ship.forward());
+            ship.update(&controls, dt);
+            for _ in 0..10 {
+                ship.update(&ShipControls::default(), dt);
+            }
+            let angle = 11.0 * ship.torque * dt * dt;
+            (before, (right(&ship), ship.up(), ship.forward()), angle)
+        };
+
+        // Cabeceo (x de la nave): la nariz sube hacia arriba de la nave
+        let ((right, up, forward), after, angle) = turn(ShipControls { torque: Vec3::x(), ..Default::default() });
+        assert_close(after.0, right, "cabeceo: derecha");
+        assert_close(after.2, forward * angle.cos() + up * angle.sin(), "cabeceo: adelante");
+
+        // Guiñada (y de la nave): la nariz gira hacia la izquierda
+        let ((right, up, forward), after, angle) = turn(ShipControls { torque: Vec3::y(), ..Default::default() });
+        assert_close(after.1, up, "guiñada: arriba");
+        assert_close(after.2, forward * angle.cos() - right * angle.sin(), "guiñada: adelante");
+
+        // Alabeo (z de la nave): la nariz no se mueve y la derecha sube
+        let ((right, up, forward), after, angle) = turn(ShipControls { torque: Vec3::z(), ..Default::default() });
+        assert_close(after.2, forward, "alabeo: adelante");
+        assert_close(after.0, right * angle.cos() + up * angle.sin(), "alabeo: derecha");
+    }
+
+    #[test]
+    fn stabilizing_stops_the_rotation() {
+        let dt = 0.1;
+        let mut ship = ship();
+        ship.angular_velocity = Vec3::new(1.0, 2.0, -0.5);
+        let axis = ship.angular_velocity.normalize();
+        let controls = ShipControls { stabilize: true, ..Default::default() };
+
+        let mut last = ship.angular_velocity.magnitude();
+        while last > 0.0 {
+            ship.update(&controls, dt);
+            let speed = ship.angular_velocity.magnitude();
+            // Frena a la aceleración de los RCS sin cambiar de eje
+            assert!((last - speed - ship.torque * dt).abs() < 1e-5 || speed == 0.0, "{last} a {speed}");
+            if speed > 0.0 {
+                assert_close(ship.angular_velocity.normalize(), axis, "eje");
+            }
+            last = speed;
+        }
+        assert_eq!(ship.angular_velocity, Vec3::zeros());
+
+        // Ya quieta, deja de girar
+        let orientation = ship.orientation;
+        ship.update(&controls, dt);
+        assert_eq!(ship.orientation, orientation);
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use nalgebra_glm::Vec2;
+
+use crate::color::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterMode {
+    Nearest,
+    Bilinear,
+    Trilinear, // Bilineal en los dos mipmaps más cercanos y mezcla entre ellos
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WrapMode {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+struct MipLevel {
+    width: usize,
+    height: usize,
+    texels: Vec<Color>,
+}
+
+// Imagen muestreable por los shaders. Guarda la cadena completa de mipmaps.
+pub struct Texture {
+    levels: Vec<MipLevel>,
+    pub filter: FilterMode,
+    pub wrap_u: WrapMode,
+    pub wrap_v: WrapMode,
+}
+
+impl Texture {
+    // Carga un PNG o JPEG. El canal alfa, si lo hay, queda como opacidad.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_rgba8();
+        let (width, height) = image.dimensions();
+        let texels = image
+            .pixels()
+            .map(|pixel| Color::new(pixel[0], pixel[1], pixel[2]).with_alpha(pixel[3] as f32 / 255.0))
+            .collect();
+
+        Ok(Texture::from_pixels(width as usize, height as usize, texels))
+    }
+
+    pub fn from_pixels(width: usize, height: usize, texels: Vec<Color>) -> Self {
+        assert_eq!(texels.len(), width * height, "la textura no tiene width * height texeles");
+
+        let mut levels = vec![MipLevel { width, height, texels }];
+        while let Some(level) = levels.last().filter(|level| level.width > 1 || level.height > 1) {
+            let next = downsample(level);
+            levels.push(next);
+        }
+
+        Texture {
+            levels,
+            filter: FilterMode::Bilinear,
+            wrap_u: WrapMode::Repeat,
+            wrap_v: WrapMode::ClampToEdge,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.levels[0].width
+    }
+
+    pub fn height(&self) -> usize {
+        self.levels[0].height
+    }
+
+    // `footprint` es cuánto avanza la coordenada de textura por píxel de
+    // pantalla; sólo se usa con filtrado trilineal para elegir el mipmap.
+    pub fn sample(&self, uv: Vec2, footprint: f32) -> Color {
+        match self.filter {
+            FilterMode::Nearest => self.sample_nearest(0, uv),
+            FilterMode::Bilinear => self.sample_bilinear(0, uv),
+            FilterMode::Trilinear => {
+                let texels_per_pixel = footprint * self.width().max(self.height()) as f32;
+                let max_level = (self.levels.len() - 1) as f32;
+                let lod = texels_per_pixel.max(1e-8).log2().clamp(0.0, max_level);
+
+                let lower = lod.floor() as usize;
+                let upper = (lower + 1).min(self.levels.len() - 1);
+                let a = self.sample_bilinear(lower, uv);
+                let b = self.sample_bilinear(upper, uv);
+                a.lerp(&b, lod.fract())
+            }
+        }
+    }
+
+    fn sample_nearest(&self, level: usize, uv: Vec2) -> Color {
+        let level = &self.levels[level];
+        let x = wrap((uv.x * level.width as f32).floor() as i64, level.width, self.wrap_u);
+        let y = wrap((uv.y * level.height as f32).floor() as i64, level.height, self.wrap_v);
+        level.texels[y * level.width + x]
+    }
+
+    fn sample_bilinear(&self, level: usize, uv: Vec2) -> Color {
+        let level = &self.levels[level];
+
+        // Centros de texel en coordenadas enteras + 0.5
+        let x = uv.x * level.width as f32 - 0.5;
+        let y = uv.y * level.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+
+        let x0 = x0 as i64;
+        let y0 = y0 as i64;
+        let texel = |dx: i64, dy: i64| {
+            let px = wrap(x0 + dx, level.width, self.wrap_u);
+            let py = wrap(y0 + dy, level.height, self.wrap_v);
+            level.texels[py * level.width + px]
+        };
+
+        let top = texel(0, 0).lerp(&texel(1, 0), tx);
+        let bottom = texel(0, 1).lerp(&texel(1, 1), tx);
+        top.lerp(&bottom, ty)
+    }
+}
+
+fn wrap(coordinate: i64, size: usize, mode: WrapMode) -> usize {
+    let size = size as i64;
+    match mode {
+        WrapMode::Repeat => coordinate.rem_euclid(size) as usize,
+        WrapMode::ClampToEdge => coordinate.clamp(0, size - 1) as usize,
+        WrapMode::MirroredRepeat => {
+            let period = coordinate.rem_euclid(2 * size);
+            if period < size { period as usize } else { (2 * size - 1 - period) as usize }
+        }
+    }
+}
+
+// Promedio (en espacio lineal) de bloques de 2x2, o 2x1 cuando una dimensión ya es 1
+fn downsample(level: &MipLevel) -> MipLevel {
+    let width = (level.width / 2).max(1);
+    let height = (level.height / 2).max(1);
+    let mut texels = Vec::with_capacity(width * height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = Color::black();
+            let mut alpha = 0.0;
+            let mut count = 0;
+            for sy in (y * 2)..((y * 2 + 2).min(level.height)) {
+                for sx in (x * 2)..((x * 2 + 2).min(level.width)) {
+                    let texel = level.texels[sy * level.width + sx];
+                    sum = sum + texel;
+                    alpha += texel.a;
+                    count += 1;
+                }
+            }
+            texels.push((sum * (1.0 / count as f32)).with_alpha(alpha / count as f32));
+        }
+    }
+
+    MipLevel { width, height, texels }
+}
@@ -0,0 +1,124 @@
+use crate::color::Color;
+
+// Operador que comprime el rango HDR a [0, 1] antes de codificar en sRGB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneOperator {
+    Clamp, // Sin compresión: recorta lo que pase de 1.0
+    Reinhard,
+    Aces,
+}
+
+impl ToneOperator {
+    pub fn next(self) -> Self {
+        match self {
+            ToneOperator::Clamp => ToneOperator::Reinhard,
+            ToneOperator::Reinhard => ToneOperator::Aces,
+            ToneOperator::Aces => ToneOperator::Clamp,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ToneOperator::Clamp => "clamp",
+            ToneOperator::Reinhard => "reinhard",
+            ToneOperator::Aces => "aces",
+        }
+    }
+
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "clamp" => Some(ToneOperator::Clamp),
+            "reinhard" => Some(ToneOperator::Reinhard),
+            "aces" => Some(ToneOperator::Aces),
+            _ => None,
+        }
+    }
+
+    // Comprime un canal lineal (ya multiplicado por la exposición)
+    fn apply(self, v: f32) -> f32 {
+        match self {
+            ToneOperator::Clamp => v,
+            ToneOperator::Reinhard => v / (1.0 + v),
+            ToneOperator::Aces => aces(v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ToneMapping {
+    pub operator: ToneOperator,
+    pub exposure: f32, // Multiplicador lineal aplicado antes del operador
+}
+
+impl Default for ToneMapping {
+    fn default() -> Self {
+        ToneMapping { operator: ToneOperator::Aces, exposure: 1.0 }
+    }
+}
+
+impl ToneMapping {
+    // Color lineal HDR a 0xRRGGBB en sRGB
+    pub fn encode(&self, color: Color) -> u32 {
+        let color = color * self.exposure;
+        let map = |v: f32| self.operator.apply(v);
+        Color::rgb(map(color.r), map(color.g), map(color.b)).to_hex()
+    }
+}
+
+// Aproximación de la curva ACES de Krzysztof Narkowicz
+fn aces(v: f32) -> f32 {
+    let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+    (v * (a * v + b)) / (v * (c * v + d) + e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERATORS: [ToneOperator; 3] = [ToneOperator::Clamp, ToneOperator::Reinhard, ToneOperator::Aces];
+
+    #[test]
+    fn operators_map_black_to_black() {
+        for operator in OPERATORS {
+            assert_eq!(operator.apply(0.0), 0.0, "{}", operator.name());
+            assert_eq!(ToneMapping { operator, exposure: 1.0 }.encode(Color::black()), 0x000000);
+        }
+    }
+
+    #[test]
+    fn operators_are_monotonic() {
+        for operator in OPERATORS {
+            let mut last = 0.0;
+            for step in 1..=2000 {
+                let value = operator.apply(step as f32 * 0.05);
+                assert!(value > last, "{}: {} en {}", operator.name(), value, step as f32 * 0.05);
+                last = value;
+            }
+        }
+    }
+
+    #[test]
+    fn reinhard_halves_one() {
+        assert_eq!(ToneOperator::Reinhard.apply(1.0), 0.5);
+        let encoded = ToneMapping { operator: ToneOperator::Reinhard, exposure: 1.0 }.encode(Color::rgb(1.0, 1.0, 1.0));
+        assert_eq!(encoded, Color::rgb(0.5, 0.5, 0.5).to_hex());
+    }
+
+    #[test]
+    fn clamp_clips_above_one() {
+        let clamp = ToneMapping { operator: ToneOperator::Clamp, exposure: 1.0 };
+        assert_eq!(clamp.encode(Color::rgb(4.0, 1.0, 0.25)), Color::rgb(1.0, 1.0, 0.25).to_hex());
+    }
+
+    #[test]
+    fn exposure_scales_the_input() {
+        let color = Color::rgb(0.02, 0.3, 1.7);
+        for operator in OPERATORS {
+            for exposure in [0.25, 2.0, 8.0] {
+                let exposed = ToneMapping { operator, exposure }.encode(color);
+                let scaled = ToneMapping { operator, exposure: 1.0 }.encode(color * exposure);
+                assert_eq!(exposed, scaled, "{} con exposición {}", operator.name(), exposure);
+            }
+        }
+    }
+}
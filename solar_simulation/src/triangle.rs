@@ -1,59 +1,83 @@
-use nalgebra_glm::{Vec3, dot};
+use nalgebra_glm::{Vec2, Vec3};
 use crate::fragment::Fragment;
 use crate::vertex::Vertex;
-use crate::color::Color;
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
-  let mut fragments = Vec::new();
+// Rasteriza el triángulo dentro del rectángulo `clip` (min_x, min_y, max_x,
+// max_y inclusivos) y entrega cada fragmento a `emit`, sin acumularlos. Los
+// píxeles que caen justo sobre un lado se quedan sólo con los lados de arriba
+// o de la izquierda (regla top-left): un lado compartido por dos triángulos
+// se dibuja una sola vez y las superficies transparentes no se mezclan dos
+// veces en la costura.
+pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, clip: (i32, i32, i32, i32), mut emit: impl FnMut(Fragment)) {
   let (a, b, c) = (v1.transformed_position, v2.transformed_position, v3.transformed_position);
 
   let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c);
-
-  let light_dir = Vec3::new(0.0, 0.0, 1.0);
+  let (min_x, min_y) = (min_x.max(clip.0), min_y.max(clip.1));
+  let (max_x, max_y) = (max_x.min(clip.2), max_y.min(clip.3));
 
   let triangle_area = edge_function(&a, &b, &c);
+  if triangle_area == 0.0 || triangle_area.is_nan() {
+    return;
+  }
+
+  // Con el área positiva, el interior es donde las tres funciones de borde son
+  // positivas sin importar el sentido en que vienen los vértices
+  let orientation = triangle_area.signum();
+  let owns_bc = is_top_left(&b, &c, orientation);
+  let owns_ca = is_top_left(&c, &a, orientation);
+  let owns_ab = is_top_left(&a, &b, orientation);
+
+  // Cuánto cambia la coordenada de textura por píxel en este triángulo
+  let tex_area = (v2.tex_coords - v1.tex_coords).perp(&(v3.tex_coords - v1.tex_coords)).abs();
+  let tex_footprint = (tex_area / triangle_area.abs()).sqrt();
+
+  let inv_w1 = 1.0 / v1.clip_position.w;
+  let inv_w2 = 1.0 / v2.clip_position.w;
+  let inv_w3 = 1.0 / v3.clip_position.w;
 
   for y in min_y..=max_y {
     for x in min_x..=max_x {
       let point = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
 
-      let (w1, w2, w3) = barycentric_coordinates(&point, &a, &b, &c, triangle_area);
-
-      if w1 >= 0.0 && w1 <= 1.0 && 
-         w2 >= 0.0 && w2 <= 1.0 &&
-         w3 >= 0.0 && w3 <= 1.0 {
+      let e1 = edge_function(&b, &c, &point) * orientation;
+      let e2 = edge_function(&c, &a, &point) * orientation;
+      let e3 = edge_function(&a, &b, &point) * orientation;
 
-        let normal = v1.transformed_normal * w1 + v2.transformed_normal * w2 + v3.transformed_normal * w3;
-        let normal = normal.normalize();
+      if covers(e1, owns_bc) && covers(e2, owns_ca) && covers(e3, owns_ab) {
+        let area = triangle_area.abs();
+        let (w1, w2, w3) = (e1 / area, e2 / area, e3 / area);
 
-        let intensity = dot(&normal, &light_dir).max(0.0);
+        // Corrección de perspectiva: los atributos se interpolan linealmente en
+        // espacio de vista, así que pesamos cada baricéntrica por 1/w
+        let (p1, p2, p3) = (w1 * inv_w1, w2 * inv_w2, w3 * inv_w3);
+        let inv_sum = 1.0 / (p1 + p2 + p3);
+        let (p1, p2, p3) = (p1 * inv_sum, p2 * inv_sum, p3 * inv_sum);
 
-        let base_color = Color::new(100, 100, 100);
-        let lit_color = base_color * intensity;
+        let normal = v1.transformed_normal * p1 + v2.transformed_normal * p2 + v3.transformed_normal * p3;
+        let normal = normal.normalize();
 
+        // z en pantalla ya es lineal en espacio de pantalla
         let depth = a.z * w1 + b.z * w2 + c.z * w3;
 
-        let vertex_position = v1.position * w1 + v2.position * w2 + v3.position * w3;
-
-        fragments.push(
-            Fragment::new(
-                x as f32,
-                y as f32,
-                lit_color,
-                depth,
-                normal,
-                intensity,
-                vertex_position,
-            )
-        );
+        let world_position = v1.world_position * p1 + v2.world_position * p2 + v3.world_position * p3;
+        let vertex_position = v1.position * p1 + v2.position * p2 + v3.position * p3;
+        let tex_coords = v1.tex_coords * p1 + v2.tex_coords * p2 + v3.tex_coords * p3;
+
+        emit(Fragment {
+            position: Vec2::new(x as f32, y as f32),
+            depth,
+            normal,
+            world_position,
+            vertex_position,
+            tex_coords,
+            tex_footprint,
+        });
       }
     }
   }
-
-  fragments
 }
 
-fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i32) {
+pub fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i32) {
     let min_x = v1.x.min(v2.x).min(v3.x).floor() as i32;
     let min_y = v1.y.min(v2.y).min(v3.y).floor() as i32;
     let max_x = v1.x.max(v2.x).max(v3.x).ceil() as i32;
@@ -62,12 +86,18 @@ fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i3
     (min_x, min_y, max_x, max_y)
 }
 
-fn barycentric_coordinates(p: &Vec3, a: &Vec3, b: &Vec3, c: &Vec3, area: f32) -> (f32, f32, f32) {
-    let w1 = edge_function(b, c, p) / area;
-    let w2 = edge_function(c, a, p) / area;
-    let w3 = edge_function(a, b, p) / area;
+// El lado de `from` a `to` es de arriba o de la izquierda. `orientation`
+// corrige el sentido para que todos los triángulos se recorran igual: así,
+// de los dos que comparten un lado cada uno lo recorre al revés que el otro
+// y exactamente uno se lo queda.
+fn is_top_left(from: &Vec3, to: &Vec3, orientation: f32) -> bool {
+    let dx = (to.x - from.x) * orientation;
+    let dy = (to.y - from.y) * orientation;
+    dy < 0.0 || (dy == 0.0 && dx > 0.0)
+}
 
-    (w1, w2, w3)
+fn covers(edge: f32, owns_edge: bool) -> bool {
+    edge > 0.0 || (edge == 0.0 && owns_edge)
 }
 
 fn edge_function(a: &Vec3, b: &Vec3, c: &Vec3) -> f32 {
@@ -75,3 +105,88 @@ fn edge_function(a: &Vec3, b: &Vec3, c: &Vec3) -> f32 {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SIZE: usize = 12;
+
+  // Cuántas veces se emite cada píxel de una grilla de SIZE x SIZE, dibujando
+  // por separado cada cuadrante como hace el rasterizador por tiles
+  fn coverage(triangles: &[[Vec3; 3]]) -> Vec<u32> {
+    let mut counts = vec![0; SIZE * SIZE];
+    let half = SIZE as i32 / 2;
+    let tiles = [(0, 0), (half, 0), (0, half), (half, half)];
+
+    for [a, b, c] in triangles {
+      let (v1, v2, v3) = (vertex(a), vertex(b), vertex(c));
+      for (x0, y0) in tiles {
+        triangle(&v1, &v2, &v3, (x0, y0, x0 + half - 1, y0 + half - 1), |fragment| {
+          counts[fragment.position.y as usize * SIZE + fragment.position.x as usize] += 1;
+        });
+      }
+    }
+    counts
+  }
+
+  fn vertex(position: &Vec3) -> Vertex {
+    Vertex::new(*position, Vec3::z(), Vec2::zeros())
+  }
+
+  // Abanico alrededor del centro de un cuadrado: los lados compartidos y los
+  // bordes pasan justo por centros de píxel, y dos triángulos vienen en el
+  // sentido contrario
+  fn fan() -> Vec<[Vec3; 3]> {
+    let corner = |x: f32, y: f32| Vec3::new(x, y, 0.0);
+    let (min, max, center) = (1.5, 10.5, 6.5);
+    vec![
+      [corner(min, min), corner(max, min), corner(center, center)],
+      [corner(center, center), corner(max, max), corner(max, min)],
+      [corner(max, max), corner(min, max), corner(center, center)],
+      [corner(center, center), corner(min, min), corner(min, max)],
+    ]
+  }
+
+  #[test]
+  fn shared_edges_are_drawn_once() {
+    let counts = coverage(&fan());
+
+    for y in 0..SIZE {
+      for x in 0..SIZE {
+        let count = counts[y * SIZE + x];
+        assert!(count <= 1, "({}, {}) se dibujó {} veces", x, y, count);
+        // Los píxeles con centro dentro del cuadrado, sin contar su borde
+        if (2..=9).contains(&x) && (2..=9).contains(&y) {
+          assert_eq!(count, 1, "({}, {})", x, y);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn adjacent_squares_split_their_border() {
+    // Dos cuadrados que comparten la columna x = 6.5: cada píxel del borde
+    // común queda en uno solo de los dos
+    let corner = |x: f32, y: f32| Vec3::new(x, y, 0.0);
+    let square = |x0: f32, x1: f32| {
+      [
+        [corner(x0, 2.5), corner(x1, 2.5), corner(x1, 8.5)],
+        [corner(x0, 2.5), corner(x1, 8.5), corner(x0, 8.5)],
+      ]
+    };
+    let triangles: Vec<[Vec3; 3]> = square(3.5, 6.5).into_iter().chain(square(6.5, 9.5)).collect();
+    let counts = coverage(&triangles);
+
+    for y in 3..8 {
+      assert_eq!(counts[y * SIZE + 6], 1, "(6, {})", y);
+    }
+    assert!(counts.iter().all(|&count| count <= 1));
+  }
+
+  #[test]
+  fn degenerate_triangles_are_skipped() {
+    let line = [Vec3::new(1.0, 1.0, 0.0), Vec3::new(5.0, 5.0, 0.0), Vec3::new(9.0, 9.0, 0.0)];
+    assert!(coverage(&[line]).iter().all(|&count| count == 0));
+  }
+}
@@ -0,0 +1,12 @@
+// Unidades físicas de la simulación: distancias en km, masas en kg y tiempo
+// en segundos. Las escenas escriben los semiejes en UA, los movimientos medios
+// en grados por día y los períodos de rotación en horas, que se pasan a estas
+// unidades al cargarlas.
+pub const AU_KM: f64 = 149_597_870.7;
+pub const DAY_SECONDS: f64 = 86_400.0;
+pub const HOUR_SECONDS: f64 = 3_600.0;
+
+// Grados por día a radianes por segundo
+pub fn degrees_per_day(value: f64) -> f64 {
+    value.to_radians() / DAY_SECONDS
+}
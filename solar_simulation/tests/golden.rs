@@ -0,0 +1,458 @@
+// Pruebas de regresión visual: renderizan escenas canónicas sin ventana y las
+// comparan contra imágenes de referencia en tests/golden/.
+//
+// Para regenerar las referencias después de un cambio visual intencional:
+//     UPDATE_GOLDEN=1 cargo test --test golden
+// Si una comparación falla, la imagen obtenida y un mapa de diferencias se
+// guardan en target/golden-diff/.
+
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use nalgebra_glm::{Mat4, Vec2, Vec3};
+
+use solar_simulation::atmosphere::{atmosphere_shader, Atmosphere};
+use solar_simulation::bloom::{apply_bloom, Bloom};
+use solar_simulation::color::Color;
+use solar_simulation::fragment::Fragment;
+use solar_simulation::framebuffer::{BlendMode, Framebuffer};
+use solar_simulation::light::Light;
+use solar_simulation::mesh::uv_sphere;
+use solar_simulation::obj::Obj;
+use solar_simulation::line::{line, polyline};
+use solar_simulation::orbit::{render_orbit, OrbitStyle, OrbitalElements};
+use solar_simulation::ring::{default_profile, profile_texture, ring_shader, Ring};
+use solar_simulation::shaders::{self, ShaderFn, ShaderOutput, ShaderParam, ShaderParams};
+use solar_simulation::shadow::{ShadowCaster, ShadowMap};
+use solar_simulation::ship::Ship;
+use solar_simulation::texture::{FilterMode, Texture};
+use solar_simulation::vertex::Vertex;
+use solar_simulation::{
+    create_model_matrix, create_noise, create_perspective_matrix, create_view_matrix,
+    create_viewport_matrix, render_blended_body, render_celestial_body, render_skybox, Uniforms, FAR_PLANE, NEAR_PLANE,
+};
+
+const WIDTH: usize = 160;
+const HEIGHT: usize = 120;
+const BACKGROUND: u32 = 0x060611;
+// Cámara fija mirando al origen
+const EYE: Vec3 = Vec3::new(0.0, 0.0, 3.0);
+// Estrella arriba a la derecha y por delante de la escena
+const LIGHT_POSITION: Vec3 = Vec3::new(6.0, 4.0, 6.0);
+
+// Diferencia máxima por canal para considerar iguales dos píxeles
+const CHANNEL_TOLERANCE: u8 = 3;
+// Fracción de píxeles que puede exceder la tolerancia (bordes de triángulos)
+const MAX_MISMATCH_RATIO: f32 = 0.002;
+
+fn new_framebuffer() -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    framebuffer.set_background_color(BACKGROUND);
+    framebuffer.clear();
+    framebuffer
+}
+
+fn uniforms(model_matrix: Mat4, time: f32) -> Uniforms {
+    Uniforms {
+        model_matrix,
+        view_matrix: create_view_matrix(EYE, Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+        projection_matrix: create_perspective_matrix(WIDTH as f32, HEIGHT as f32, NEAR_PLANE, FAR_PLANE),
+        viewport_matrix: create_viewport_matrix(WIDTH as f32, HEIGHT as f32),
+        time,
+        noise: create_noise(),
+        shader_params: ShaderParams::default(),
+        texture: None,
+        lights: vec![Light { position: LIGHT_POSITION, intensity: 1.0 }],
+        shadow_maps: Vec::new(),
+        camera_position: EYE,
+        atmosphere: None,
+    }
+}
+
+fn load_model(path: &str) -> Vec<Vertex> {
+    Obj::load(path).expect("no se pudo cargar el modelo").get_vertex_array()
+}
+
+fn render_sphere(shader: ShaderFn) -> Framebuffer {
+    let mut framebuffer = new_framebuffer();
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 2.0, Vec3::new(0.3, 0.5, 0.0));
+    render_celestial_body(&mut framebuffer, &load_model("assets/sphere-1.obj"), &uniforms(model_matrix, 10.0), shader);
+    framebuffer
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+fn diff_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("target").join("golden-diff")
+}
+
+fn load_png(path: &Path) -> (usize, usize, Vec<u32>) {
+    let decoder = png::Decoder::new(File::open(path).unwrap_or_else(|err| {
+        panic!("falta la referencia {} ({}); genérala con UPDATE_GOLDEN=1", path.display(), err)
+    }));
+    let mut reader = decoder.read_info().expect("PNG inválido");
+    let mut bytes = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut bytes).expect("PNG inválido");
+    assert_eq!(info.color_type, png::ColorType::Rgb, "las referencias deben ser RGB de 8 bits");
+
+    let pixels = bytes[..info.buffer_size()]
+        .chunks(3)
+        .map(|rgb| ((rgb[0] as u32) << 16) | ((rgb[1] as u32) << 8) | rgb[2] as u32)
+        .collect();
+
+    (info.width as usize, info.height as usize, pixels)
+}
+
+fn save_diff(path: &Path, width: usize, height: usize, pixels: &[u32]) {
+    let file = BufWriter::new(File::create(path).expect("no se pudo crear el diff"));
+    let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let bytes: Vec<u8> = pixels
+        .iter()
+        .flat_map(|&pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8])
+        .collect();
+    encoder.write_header().unwrap().write_image_data(&bytes).unwrap();
+}
+
+fn channel_difference(a: u32, b: u32) -> u8 {
+    [16, 8, 0]
+        .iter()
+        .map(|shift| (((a >> shift) & 0xFF) as i32 - ((b >> shift) & 0xFF) as i32).unsigned_abs() as u8)
+        .max()
+        .unwrap()
+}
+
+// Aplica el tone mapping por defecto y compara lo que se presentaría
+fn assert_golden(name: &str, framebuffer: &mut Framebuffer) {
+    framebuffer.resolve();
+    let reference = golden_dir().join(format!("{}.png", name));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(golden_dir()).unwrap();
+        framebuffer.save_png(&reference).unwrap();
+        return;
+    }
+
+    let (width, height, expected) = load_png(&reference);
+    assert_eq!((width, height), (framebuffer.width, framebuffer.height), "{}: tamaño distinto", name);
+
+    // Mapa de diferencias: rojo donde falla, la referencia atenuada donde coincide
+    let mut mismatches = 0;
+    let diff: Vec<u32> = expected
+        .iter()
+        .zip(&framebuffer.buffer)
+        .map(|(&expected, &actual)| {
+            if channel_difference(expected, actual) > CHANNEL_TOLERANCE {
+                mismatches += 1;
+                0xFF0000
+            } else {
+                (expected >> 2) & 0x3F3F3F
+            }
+        })
+        .collect();
+
+    let ratio = mismatches as f32 / expected.len() as f32;
+    if ratio > MAX_MISMATCH_RATIO {
+        fs::create_dir_all(diff_dir()).unwrap();
+        let actual_path = diff_dir().join(format!("{}_actual.png", name));
+        let diff_path = diff_dir().join(format!("{}_diff.png", name));
+        framebuffer.save_png(&actual_path).unwrap();
+        save_diff(&diff_path, width, height, &diff);
+
+        panic!(
+            "{}: {} píxeles distintos ({:.2}%), ver {} y {}",
+            name,
+            mismatches,
+            ratio * 100.0,
+            actual_path.display(),
+            diff_path.display(),
+        );
+    }
+}
+
+#[test]
+fn sphere_with_each_shader() {
+    for name in shaders::SHADER_NAMES {
+        let shader = shaders::shader_by_name(name).unwrap();
+        assert_golden(&format!("sphere_{}", name), &mut render_sphere(shader));
+    }
+}
+
+#[test]
+fn sphere_lit_from_the_side() {
+    // La luz está a la izquierda: la mitad derecha queda en el lado nocturno
+    let mut framebuffer = new_framebuffer();
+    let mut uniforms = uniforms(create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 2.0, Vec3::new(0.0, 0.0, 0.0)), 10.0);
+    uniforms.lights = vec![Light { position: Vec3::new(-10.0, 0.0, 0.0), intensity: 1.0 }];
+    render_celestial_body(&mut framebuffer, &load_model("assets/sphere-1.obj"), &uniforms, shaders::rocky_planet_shader);
+    assert_golden("sphere_side_light", &mut framebuffer);
+}
+
+#[test]
+fn moon_shadow_on_planet() {
+    // Eclipse: la luna queda entre la estrella y el planeta y su sombra cae
+    // sobre el lado iluminado
+    let sphere = load_model("assets/sphere-1.obj");
+    let light_position = Vec3::new(-8.0, 1.0, 3.0);
+    let planet = create_model_matrix(Vec3::new(0.3, 0.0, 0.0), 1.6, Vec3::new(0.0, 0.0, 0.0));
+    let moon = create_model_matrix(Vec3::new(-1.2, 0.15, 0.75), 0.5, Vec3::new(0.0, 0.0, 0.0));
+
+    let casters = [
+        ShadowCaster { model_matrix: planet, vertices: &sphere },
+        ShadowCaster { model_matrix: moon, vertices: &sphere },
+    ];
+
+    let mut framebuffer = new_framebuffer();
+    let mut uniforms = uniforms(planet, 10.0);
+    uniforms.lights = vec![Light { position: light_position, intensity: 1.0 }];
+    uniforms.shadow_maps = vec![ShadowMap::render(light_position, 256, &casters)];
+
+    render_celestial_body(&mut framebuffer, &sphere, &uniforms, shaders::rocky_planet_shader);
+    uniforms.model_matrix = moon;
+    render_celestial_body(&mut framebuffer, &sphere, &uniforms, shaders::moon_shader);
+    assert_golden("moon_shadow", &mut framebuffer);
+}
+
+#[test]
+fn star_bloom() {
+    // El resplandor de la estrella se extiende sobre el fondo; el planeta de
+    // adelante no emite y tapa su parte del brillo
+    let sphere = load_model("assets/sphere-1.obj");
+    let mut framebuffer = new_framebuffer();
+
+    let star = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.2, Vec3::new(0.0, 0.0, 0.0));
+    render_celestial_body(&mut framebuffer, &sphere, &uniforms(star, 10.0), shaders::star_shader);
+    let planet = create_model_matrix(Vec3::new(0.35, 0.1, 1.2), 0.4, Vec3::new(0.0, 0.0, 0.0));
+    render_celestial_body(&mut framebuffer, &sphere, &uniforms(planet, 10.0), shaders::rocky_planet_shader);
+
+    apply_bloom(&mut framebuffer, &Bloom::default());
+    assert_golden("star_bloom", &mut framebuffer);
+}
+
+#[test]
+fn transparent_cloud_shell() {
+    // Capa de nubes semitransparente sobre la Tierra: el cielo se vuelve
+    // invisible y las nubes dejan ver un poco de la superficie
+    let sphere = load_model("assets/sphere-1.obj");
+    let mut framebuffer = new_framebuffer();
+
+    let earth = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 2.0, Vec3::new(0.3, 0.5, 0.0));
+    render_celestial_body(&mut framebuffer, &sphere, &uniforms(earth, 10.0), shaders::earth_shader);
+
+    let shell = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 2.1, Vec3::new(0.3, 0.5, 0.0));
+    let mut uniforms = uniforms(shell, 10.0);
+    uniforms.shader_params.insert("sky_opacity", ShaderParam::Number(0.0));
+    uniforms.shader_params.insert("cloud_opacity", ShaderParam::Number(0.7));
+    let cloud_shader = shaders::shader_by_name("cloud").unwrap();
+    render_blended_body(&mut framebuffer, &sphere, &uniforms, cloud_shader, BlendMode::Alpha);
+
+    assert_golden("cloud_shell", &mut framebuffer);
+}
+
+#[test]
+fn atmosphere_limb_and_terminator() {
+    // Luz de costado: halo azul en el limbo iluminado y enrojecimiento junto
+    // al terminador; del lado nocturno la atmósfera no brilla
+    let mut framebuffer = new_framebuffer();
+    let light = Light { position: Vec3::new(-10.0, 2.0, 0.0), intensity: 1.0 };
+
+    let earth = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.6, Vec3::new(0.0, 0.0, 0.0));
+    let mut uniforms = uniforms(earth, 10.0);
+    uniforms.lights = vec![light];
+    render_celestial_body(&mut framebuffer, &load_model("assets/sphere-1.obj"), &uniforms, shaders::earth_shader);
+
+    let atmosphere = Atmosphere::default();
+    uniforms.model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.6 * (1.0 + atmosphere.thickness), Vec3::new(0.0, 0.0, 0.0));
+    uniforms.atmosphere = Some(atmosphere);
+    render_blended_body(&mut framebuffer, &uv_sphere(24, 48), &uniforms, atmosphere_shader, BlendMode::Additive);
+
+    assert_golden("atmosphere", &mut framebuffer);
+}
+
+#[test]
+fn ring_with_planet_shadow() {
+    // Anillos inclinados hacia la cámara con la luz arriba a la izquierda: el
+    // planeta proyecta su sombra sobre el lado derecho del anillo
+    let sphere = uv_sphere(24, 48);
+    let light_position = Vec3::new(-8.0, 4.0, 1.0);
+    let planet = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 0.9, Vec3::new(0.0, 0.0, 0.0));
+    let ring = Ring::new(1.3, 2.3, 0.0, 1.0, Arc::new(profile_texture(&default_profile())));
+    let tilt = 0.7;
+
+    let mut framebuffer = new_framebuffer();
+    let mut uniforms = uniforms(planet, 10.0);
+    uniforms.lights = vec![Light { position: light_position, intensity: 1.0 }];
+    uniforms.shadow_maps = vec![ShadowMap::render(light_position, 256, &[ShadowCaster { model_matrix: planet, vertices: &sphere }])];
+    render_celestial_body(&mut framebuffer, &sphere, &uniforms, shaders::ringed_planet_shader);
+
+    uniforms.model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 0.9, Vec3::new(tilt, 0.0, 0.0));
+    uniforms.texture = Some(ring.profile.clone());
+    render_blended_body(&mut framebuffer, &ring.vertices, &uniforms, ring_shader, BlendMode::Alpha);
+
+    assert_golden("ring_shadow", &mut framebuffer);
+}
+
+#[test]
+fn ship_model() {
+    let mut framebuffer = new_framebuffer();
+    let position = Vec3::new(0.0, -0.1, 0.0);
+    let ship = Ship::new(position, position + Vec3::new(1.0, -0.4, -0.6), 2.4, 0.2, 1.5);
+    ship.render(&mut framebuffer, &load_model("assets/nave.obj"), &mut uniforms(Mat4::identity(), 10.0));
+    assert_golden("ship", &mut framebuffer);
+}
+
+#[test]
+fn orbit_lines() {
+    // Órbitas en el mundo: una inclinada pasa por delante y por detrás de la
+    // esfera (que tapa su parte de atrás), otra es excéntrica y la tercera
+    // cruza el plano cercano de la cámara. Todas se desvanecen desde el cuerpo.
+    let mut framebuffer = new_framebuffer();
+    let sphere = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 0.6, Vec3::new(0.0, 0.0, 0.0));
+    let uniforms = uniforms(sphere, 10.0);
+    render_celestial_body(&mut framebuffer, &load_model("assets/sphere-1.obj"), &uniforms, shaders::rocky_planet_shader);
+
+    let orbit = |semi_major_axis: f32, eccentricity: f32, inclination: f32, argument_periapsis: f32| OrbitalElements {
+        semi_major_axis,
+        eccentricity,
+        inclination: inclination.to_radians(),
+        longitude_ascending_node: 0.0,
+        argument_periapsis: argument_periapsis.to_radians(),
+        mean_anomaly_epoch: 0.0,
+        mean_motion: 1.0,
+    };
+    let style = OrbitStyle { color: Color::from_hex(0xFFD966), fade: true, thickness: 1.0 };
+    let place = |offset: Vec3| offset;
+    render_orbit(&mut framebuffer, &uniforms, &orbit(0.8, 0.0, 60.0, 0.0), &place, 0.0, &style);
+    render_orbit(&mut framebuffer, &uniforms, &orbit(1.0, 0.5, 20.0, 90.0), &place, 2.0, &style);
+    let wide = OrbitStyle { color: Color::from_hex(0x8090FF), fade: false, thickness: 2.0 };
+    render_orbit(&mut framebuffer, &uniforms, &orbit(3.2, 0.0, 80.0, 0.0), &place, 0.0, &wide);
+    assert_golden("orbits", &mut framebuffer);
+}
+
+#[test]
+fn antialiased_lines() {
+    // Abanico de líneas en pantalla con distintos grosores (de medio píxel a
+    // cuatro) y el color interpolado entre los extremos, más ejes en el mundo
+    // que atraviesan una esfera: la parte de adentro y la de atrás quedan tapadas.
+    let mut framebuffer = new_framebuffer();
+    let sphere = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 0.8, Vec3::new(0.0, 0.0, 0.0));
+    let uniforms = uniforms(sphere, 10.0);
+    render_celestial_body(&mut framebuffer, &load_model("assets/sphere-1.obj"), &uniforms, shaders::moon_shader);
+
+    let screen_point = |x: f32, y: f32, color: u32| {
+        let mut vertex = Vertex::new_with_color(Vec3::zeros(), Color::from_hex(color));
+        vertex.transformed_position = Vec3::new(x, y, 0.0);
+        vertex
+    };
+    for (i, thickness) in [0.5, 1.0, 1.5, 2.5, 4.0].into_iter().enumerate() {
+        let angle = 0.15 + 0.3 * i as f32;
+        let (x, y) = (8.0 + 60.0 * angle.cos(), 112.0 - 60.0 * angle.sin());
+        line(&mut framebuffer, &screen_point(8.0, 112.0, 0xFF4040), &screen_point(x, y, 0x40FFFF), thickness);
+    }
+
+    let axis = |direction: Vec3, color: u32| {
+        let color = Color::from_hex(color);
+        [Vertex::new_with_color(-direction, color), Vertex::new_with_color(direction, color)]
+    };
+    polyline(&mut framebuffer, &uniforms, &axis(Vec3::new(1.2, 0.0, 0.0), 0xFF6060), 1.0);
+    polyline(&mut framebuffer, &uniforms, &axis(Vec3::new(0.0, 1.0, 0.0), 0x60FF60), 2.0);
+    polyline(&mut framebuffer, &uniforms, &axis(Vec3::new(0.6, 0.6, 1.5), 0x6080FF), 1.5);
+    assert_golden("lines", &mut framebuffer);
+}
+
+#[test]
+fn sphere_crossing_near_plane() {
+    // La cámara (z = 3) queda dentro de la esfera: sin recorte esto generaba
+    // triángulos invertidos que cubrían la pantalla
+    let mut framebuffer = new_framebuffer();
+    let model_matrix = create_model_matrix(Vec3::new(0.4, 0.0, 2.5), 2.0, Vec3::new(0.0, 0.0, 0.0));
+    render_celestial_body(&mut framebuffer, &load_model("assets/sphere-1.obj"), &uniforms(model_matrix, 10.0), shaders::rocky_planet_shader);
+    assert_golden("sphere_near_plane", &mut framebuffer);
+}
+
+#[test]
+fn sphere_over_skybox_stars() {
+    // Las estrellas son el fondo: un cuerpo opaco dibujado encima las tapa
+    let stars: Vec<(usize, usize)> = (0..HEIGHT).step_by(6).flat_map(|y| (0..WIDTH).step_by(6).map(move |x| (x, y))).collect();
+    let mut framebuffer = new_framebuffer();
+    render_skybox(&mut framebuffer, &stars, 0xFFFFFF);
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 2.0, Vec3::new(0.3, 0.5, 0.0));
+    render_celestial_body(&mut framebuffer, &load_model("assets/sphere-1.obj"), &uniforms(model_matrix, 10.0), shaders::moon_shader);
+
+    let alone = render_sphere(shaders::moon_shader);
+    let covered: Vec<usize> = (0..WIDTH * HEIGHT).filter(|&index| alone.zbuffer[index].is_finite()).collect();
+    assert!(stars.iter().any(|&(x, y)| covered.contains(&(y * WIDTH + x))));
+    for index in covered {
+        assert_eq!(framebuffer.hdr[index], alone.hdr[index], "píxel {}", index);
+    }
+    assert_golden("sphere_over_stars", &mut framebuffer);
+}
+
+// Tablero de ajedrez según las coordenadas de textura interpoladas
+fn checker_shader(fragment: &Fragment, _uniforms: &Uniforms) -> ShaderOutput {
+    let u = (fragment.tex_coords.x * 8.0).floor() as i32;
+    let v = (fragment.tex_coords.y * 8.0).floor() as i32;
+    if (u + v) % 2 == 0 {
+        Color::new(230, 230, 230).into()
+    } else {
+        Color::new(40, 90, 160).into()
+    }
+}
+
+#[test]
+fn perspective_correct_tex_coords() {
+    // Un plano que se aleja de la cámara: con interpolación en pantalla las
+    // casillas del tablero se doblan a lo largo de la diagonal
+    let corner = |x: f32, z: f32, u: f32, v: f32| {
+        Vertex::new(Vec3::new(x, -0.6, z), Vec3::new(0.0, 1.0, 0.0), Vec2::new(u, v))
+    };
+    let quad = [
+        corner(-1.0, 2.5, 0.0, 0.0), corner(1.0, 2.5, 1.0, 0.0), corner(1.0, -6.0, 1.0, 1.0),
+        corner(-1.0, 2.5, 0.0, 0.0), corner(1.0, -6.0, 1.0, 1.0), corner(-1.0, -6.0, 0.0, 1.0),
+    ];
+
+    let mut framebuffer = new_framebuffer();
+    render_celestial_body(&mut framebuffer, &quad, &uniforms(Mat4::identity(), 0.0), checker_shader);
+    assert_golden("perspective_tex_coords", &mut framebuffer);
+}
+
+// Mapa de 64x32 con casillas de 4 texeles y una franja roja en el ecuador
+fn checker_texture(filter: FilterMode) -> Texture {
+    let (width, height) = (64, 32);
+    let texels = (0..width * height)
+        .map(|i| {
+            let (x, y) = (i % width, i / width);
+            if y == height / 2 {
+                Color::new(200, 40, 30)
+            } else if (x / 4 + y / 4) % 2 == 0 {
+                Color::new(240, 240, 240)
+            } else {
+                Color::new(30, 60, 120)
+            }
+        })
+        .collect();
+
+    let mut texture = Texture::from_pixels(width, height, texels);
+    texture.filter = filter;
+    texture
+}
+
+#[test]
+fn textured_sphere_with_each_filter() {
+    let sphere = uv_sphere(24, 48);
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 2.0, Vec3::new(0.3, 0.5, 0.0));
+
+    for (name, filter) in [("nearest", FilterMode::Nearest), ("bilinear", FilterMode::Bilinear), ("trilinear", FilterMode::Trilinear)] {
+        let mut uniforms = uniforms(model_matrix, 0.0);
+        uniforms.texture = Some(Arc::new(checker_texture(filter)));
+
+        let mut framebuffer = new_framebuffer();
+        render_celestial_body(&mut framebuffer, &sphere, &uniforms, shaders::texture_shader);
+        assert_golden(&format!("textured_sphere_{}", name), &mut framebuffer);
+    }
+}